@@ -0,0 +1,291 @@
+//! 设备 标签/别名 管理
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::ConfigRef, http_client::PushClient, MobPushConfig};
+
+use super::{post_signed, ApiError};
+
+/// 设备的 标签/别名 信息
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    /// 设备 registration id
+    pub registration_id: String,
+    /// 设备别名
+    #[serde(default)]
+    pub alias: Option<String>,
+    /// 设备标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 标签下的设备列表
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagDevices {
+    /// 设备 registration id
+    #[serde(default)]
+    pub registration_ids: Vec<String>,
+    /// 设备总数
+    #[serde(default)]
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TagOperation {
+    Add,
+    Remove,
+    Clear,
+}
+
+impl Serialize for TagOperation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(match self {
+            TagOperation::Add => 1,
+            TagOperation::Remove => 2,
+            TagOperation::Clear => 3,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateTags<'r> {
+    registration_id: &'r str,
+    tags: Vec<&'r str>,
+    op_type: TagOperation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateAlias<'r> {
+    registration_id: &'r str,
+    alias: &'r str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryDevice<'r> {
+    registration_id: &'r str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryByTag<'r> {
+    tags: [&'r str; 1],
+    page_index: u32,
+    page_size: u32,
+}
+
+/// 设备 标签/别名 管理客户端
+///
+/// 可将用户订阅以标签形式同步到 Mob,
+/// 推送时使用 [`PushTarget::Tags`](crate::PushTarget::Tags) 按标签推送
+pub struct DeviceClient<C: PushClient> {
+    client: C,
+    config: ConfigRef,
+}
+
+impl<C: PushClient> DeviceClient<C> {
+    /// 使用指定的配置创建客户端
+    ///
+    /// 可通过 [`global_config`](crate::global_config) 使用全局配置
+    pub fn new(client: C, config: impl Into<Arc<MobPushConfig>>) -> Self {
+        Self {
+            client,
+            config: ConfigRef::new(config),
+        }
+    }
+
+    async fn update_tags<'r>(
+        &self,
+        rid: &'r str,
+        tags: impl IntoIterator<Item = &'r str>,
+        op_type: TagOperation,
+    ) -> Result<(), ApiError<C>> {
+        let body = UpdateTags {
+            registration_id: rid,
+            tags: tags.into_iter().collect(),
+            op_type,
+        };
+        post_signed::<_, _, serde_json::Value>(
+            &self.client,
+            self.config.get(),
+            "/device-v3/updateTags",
+            &body,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// 为设备添加标签
+    pub async fn add_tags<'r>(
+        &self,
+        rid: &'r str,
+        tags: impl IntoIterator<Item = &'r str>,
+    ) -> Result<(), ApiError<C>> {
+        self.update_tags(rid, tags, TagOperation::Add).await
+    }
+
+    /// 移除设备的标签
+    pub async fn remove_tags<'r>(
+        &self,
+        rid: &'r str,
+        tags: impl IntoIterator<Item = &'r str>,
+    ) -> Result<(), ApiError<C>> {
+        self.update_tags(rid, tags, TagOperation::Remove).await
+    }
+
+    /// 清空设备的全部标签
+    pub async fn clear_tags(&self, rid: &str) -> Result<(), ApiError<C>> {
+        self.update_tags(rid, [], TagOperation::Clear).await
+    }
+
+    /// 设置设备别名
+    pub async fn set_alias(&self, rid: &str, alias: &str) -> Result<(), ApiError<C>> {
+        let body = UpdateAlias {
+            registration_id: rid,
+            alias,
+        };
+        post_signed::<_, _, serde_json::Value>(
+            &self.client,
+            self.config.get(),
+            "/device-v3/updateAlias",
+            &body,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// 清除设备别名
+    pub async fn clear_alias(&self, rid: &str) -> Result<(), ApiError<C>> {
+        self.set_alias(rid, "").await
+    }
+
+    /// 查询设备的 标签/别名
+    pub async fn query_device(&self, rid: &str) -> Result<Option<DeviceInfo>, ApiError<C>> {
+        let body = QueryDevice {
+            registration_id: rid,
+        };
+        post_signed(&self.client, self.config.get(), "/device-v3/getById", &body).await
+    }
+
+    /// 分页查询标签下的设备, `page_index` 从 1 开始
+    pub async fn list_devices_by_tag(
+        &self,
+        tag: &str,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<TagDevices, ApiError<C>> {
+        let body = QueryByTag {
+            tags: [tag],
+            page_index,
+            page_size,
+        };
+        post_signed(
+            &self.client,
+            self.config.get(),
+            "/device-v3/queryByTags",
+            &body,
+        )
+        .await
+        .map(Option::unwrap_or_default)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::{
+        api::ApiError,
+        test_support::{MockResponse, RecordingClient},
+    };
+
+    use super::DeviceClient;
+
+    fn new_client() -> (RecordingClient, DeviceClient<RecordingClient>) {
+        let recording = RecordingClient::new();
+        let client = DeviceClient::new(recording.clone(), RecordingClient::config());
+        (recording, client)
+    }
+
+    fn success_with(res: serde_json::Value) -> MockResponse {
+        MockResponse::Http {
+            status: 200,
+            body: json!({"status": 200, "res": res}).to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let (recording, client) = new_client();
+        client.add_tags("rid", ["a", "b"]).await.unwrap();
+        client.remove_tags("rid", ["a"]).await.unwrap();
+        client.set_alias("rid", "alias").await.unwrap();
+        client.clear_alias("rid").await.unwrap();
+
+        let calls = recording.calls();
+        assert!(calls
+            .iter()
+            .all(|call| call.is_signed_by(&RecordingClient::config())));
+        assert_eq!(calls[0].path(), "/device-v3/updateTags");
+        assert_eq!(
+            calls[0].body,
+            json!({"registrationId": "rid", "tags": ["a", "b"], "opType": 1})
+        );
+        assert_eq!(calls[1].body["opType"], 2);
+        assert_eq!(calls[2].path(), "/device-v3/updateAlias");
+        assert_eq!(
+            calls[2].body,
+            json!({"registrationId": "rid", "alias": "alias"})
+        );
+        assert_eq!(calls[3].body["alias"], "");
+
+        recording.script(MockResponse::new_mob(500, "busy"));
+        let err = client.clear_tags("rid").await.unwrap_err();
+        assert!(matches!(err, ApiError::Mob { state: 500, .. }));
+        assert_eq!(recording.calls()[4].body["opType"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let (recording, client) = new_client();
+        recording
+            .script(success_with(
+                json!({"registrationId": "rid", "alias": "alias", "tags": ["a"]}),
+            ))
+            .script(success_with(
+                json!({"registrationIds": ["rid"], "total": 1}),
+            ));
+
+        let device = client.query_device("rid").await.unwrap().unwrap();
+        assert_eq!(device.registration_id, "rid");
+        assert_eq!(device.alias.as_deref(), Some("alias"));
+        assert_eq!(device.tags, ["a"]);
+
+        let devices = client.list_devices_by_tag("a", 1, 20).await.unwrap();
+        assert_eq!(devices.registration_ids, ["rid"]);
+        assert_eq!(devices.total, 1);
+
+        let calls = recording.calls();
+        assert_eq!(calls[0].path(), "/device-v3/getById");
+        assert_eq!(calls[0].body, json!({"registrationId": "rid"}));
+        assert_eq!(calls[1].path(), "/device-v3/queryByTags");
+        assert_eq!(
+            calls[1].body,
+            json!({"tags": ["a"], "pageIndex": 1, "pageSize": 20})
+        );
+
+        // 响应中缺少结果时
+        assert!(client.query_device("rid").await.unwrap().is_none());
+        let devices = client.list_devices_by_tag("a", 2, 20).await.unwrap();
+        assert!(devices.registration_ids.is_empty());
+    }
+}
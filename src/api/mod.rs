@@ -0,0 +1,268 @@
+//! 基于 [`PushClient`] 的 Mob Push 接口调用
+//!
+//! 全部请求均使用与 `createPush` 相同的签名方式:
+//! `sign` 请求头为 `md5(请求体 + app secret)`
+
+pub mod device;
+pub mod push;
+pub mod stats;
+
+use std::fmt::{Debug, Display};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::info;
+
+use crate::{
+    http_client::{PushClient, PushRequestBuilder, PushResponse},
+    MobPushConfig,
+};
+
+pub(crate) const MOB_API_HOST: &str = "https://api.push.mob.com";
+
+/// Mob Push 接口调用期间的异常
+pub enum ApiError<C: PushClient> {
+    /// 构建请求时异常, 如请求头或 URL 不合法
+    Build(C::Error),
+    /// 发起请求时异常
+    Request(C::Error),
+    /// json 序列化、反序列化异常
+    Json(serde_json::Error),
+    /// mob 推送响应异常
+    Mob { state: u16, msg: String },
+    /// HTTP 响应状态异常, 响应体至多保留 [`MAX_ERROR_BODY`] 个字符
+    Http { status: u16, body: String },
+    /// 请求超时
+    Timeout,
+}
+
+impl<C> Debug for ApiError<C>
+where
+    C: PushClient,
+    C::Error: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Build(err) => f.debug_tuple("Build").field(err).finish(),
+            Self::Request(err) => f.debug_tuple("Request").field(err).finish(),
+            Self::Json(err) => f.debug_tuple("Json").field(err).finish(),
+            Self::Mob { state, msg } => f
+                .debug_struct("Mob")
+                .field("state", state)
+                .field("msg", msg)
+                .finish(),
+            Self::Http { status, body } => f
+                .debug_struct("Http")
+                .field("status", status)
+                .field("body", body)
+                .finish(),
+            Self::Timeout => f.write_str("Timeout"),
+        }
+    }
+}
+
+impl<C> Display for ApiError<C>
+where
+    C: PushClient,
+    C::Error: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Build(err) => write!(f, "Build Request Error : {err}"),
+            Self::Request(err) => write!(f, "Request Error : {err}"),
+            Self::Json(err) => write!(f, "Json Error : {err}"),
+            Self::Mob { state, msg } => write!(f, "Mob Pusher Error : [{}] {}", state, msg),
+            Self::Http { status, body } => write!(f, "Http Error : [{status}] {body}"),
+            Self::Timeout => write!(f, "Request Timeout"),
+        }
+    }
+}
+
+impl<C> std::error::Error for ApiError<C>
+where
+    C: PushClient,
+    C::Error: std::error::Error,
+{
+}
+
+impl<C: PushClient> ApiError<C> {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            ApiError::Build(_) => ErrorKind::Permanent,
+            ApiError::Request(_) | ApiError::Timeout => ErrorKind::Transport,
+            ApiError::Json(_) => ErrorKind::Permanent,
+            ApiError::Mob { state, .. } | ApiError::Http { status: state, .. } => {
+                ErrorKind::Status(*state)
+            }
+        }
+    }
+
+    /// 异常是否为暂时性的, 重试后可能成功
+    ///
+    /// 参见 [`MobPushError::is_retryable`](crate::MobPushError::is_retryable)
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+
+    /// 异常是否为 Mob 限流
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind().is_rate_limited()
+    }
+}
+
+/// Mob 限流时的响应状态
+const RATE_LIMITED_STATE: u16 = 429;
+
+/// 异常的重试分类, [`ApiError`] 与 [`MobPushError`](crate::MobPushError) 共用
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorKind {
+    /// 请求发送失败或超时, 可重试
+    Transport,
+    /// Mob 或 HTTP 响应状态, 仅限流与服务端异常可重试
+    Status(u16),
+    /// 请求构建、序列化等异常, 重试也不会成功
+    Permanent,
+}
+
+impl ErrorKind {
+    pub(crate) fn is_retryable(self) -> bool {
+        match self {
+            ErrorKind::Transport => true,
+            ErrorKind::Status(state) => state == RATE_LIMITED_STATE || state >= 500,
+            ErrorKind::Permanent => false,
+        }
+    }
+
+    pub(crate) fn is_rate_limited(self) -> bool {
+        self == ErrorKind::Status(RATE_LIMITED_STATE)
+    }
+}
+
+/// 异常响应体保留的最大字符数
+pub const MAX_ERROR_BODY: usize = 256;
+
+/// 将异常响应体转换为字符串, 超出 [`MAX_ERROR_BODY`] 的部分被截断
+pub(crate) fn truncate_body(body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    let body = body.trim();
+    match body.char_indices().nth(MAX_ERROR_BODY) {
+        Some((idx, _)) => format!("{}...", &body[..idx]),
+        None => body.to_owned(),
+    }
+}
+
+impl<C: PushClient> From<serde_json::Error> for ApiError<C> {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+/// Mob Push 接口的通用响应
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct Respond<T> {
+    pub(crate) status: u16,
+    pub(crate) res: Option<T>,
+    pub(crate) error: Option<String>,
+}
+
+/// 计算请求体的签名
+pub(crate) fn sign(payload: &[u8], secret: &str) -> String {
+    let mut md5 = md5::Context::new();
+    md5.consume(payload);
+    md5.consume(secret.as_bytes());
+    format!("{:x}", md5.compute())
+}
+
+/// 向 Mob Push 接口发送签名后的请求, 并解析响应中的 `res` 字段
+pub(crate) async fn post_signed<C, B, T>(
+    client: &C,
+    config: &MobPushConfig,
+    path: &str,
+    body: &B,
+) -> Result<Option<T>, ApiError<C>>
+where
+    C: PushClient,
+    B: Serialize,
+    T: DeserializeOwned,
+{
+    let payload = serde_json::to_vec(body)?;
+    let sign = sign(&payload, &config.secret);
+
+    info!(
+        event = "Prepare to Request",
+        api.path = path,
+        api.payload.len = payload.len(),
+        api.sign = sign
+    );
+
+    let url = config.api_url(path);
+    let req = client
+        .post(url)
+        .default_headers(config)
+        .header("sign", &sign)
+        .body(payload)
+        .build()
+        .map_err(ApiError::Build)?;
+
+    let request = async {
+        let resp = client.send_request(req).await?;
+        let status = resp.status();
+        resp.bytes().await.map(|body| (status, body))
+    };
+    let resp = match config.request_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, request)
+            .await
+            .map_err(|_| ApiError::Timeout)?,
+        None => request.await,
+    }
+    .map_err(ApiError::Request)?;
+
+    // 代理等返回的异常响应体可能不是 JSON
+    let (status, body) = resp;
+    if !(200..300).contains(&status) {
+        return Err(ApiError::Http {
+            status,
+            body: truncate_body(&body),
+        });
+    }
+    let resp: Respond<T> = serde_json::from_slice(&body)?;
+
+    match resp.status {
+        200 => Ok(resp.res),
+        state => Err(ApiError::Mob {
+            state,
+            msg: resp.error.unwrap_or_default(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_support::{RecordingClient, RecordingError};
+
+    use super::ApiError;
+
+    #[test]
+    fn test_retryable() {
+        let err = ApiError::<RecordingClient>::Build(RecordingError("bad header".into()));
+        assert!(!err.is_retryable());
+        let err = ApiError::<RecordingClient>::Request(RecordingError("reset".into()));
+        assert!(err.is_retryable());
+        assert!(ApiError::<RecordingClient>::Timeout.is_retryable());
+
+        let err = ApiError::<RecordingClient>::Mob {
+            state: 429,
+            msg: String::new(),
+        };
+        assert!(err.is_retryable() && err.is_rate_limited());
+        let err = ApiError::<RecordingClient>::Http {
+            status: 503,
+            body: String::new(),
+        };
+        assert!(err.is_retryable() && !err.is_rate_limited());
+        let err = ApiError::<RecordingClient>::Mob {
+            state: 400,
+            msg: String::new(),
+        };
+        assert!(!err.is_retryable());
+    }
+}
@@ -0,0 +1,360 @@
+//! 已创建推送的查询、撤回与替换
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::ConfigRef,
+    http_client::PushClient,
+    message_kind::NotifyType,
+    pusher::push_model::{Forward, PushNotify},
+    MobPushConfig, PushEntity, PushResult,
+};
+
+use super::{post_signed, ApiError};
+
+/// 推送任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushState {
+    /// 等待发送
+    Pending,
+    /// 发送中
+    Sending,
+    /// 发送完成
+    Finished,
+    /// 发送失败
+    Failed,
+    /// 已取消
+    Cancelled,
+    /// 未知状态
+    Unknown(i32),
+}
+
+impl<'de> Deserialize<'de> for PushState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(match i32::deserialize(deserializer)? {
+            1 => PushState::Pending,
+            2 => PushState::Sending,
+            3 => PushState::Finished,
+            4 => PushState::Failed,
+            5 => PushState::Cancelled,
+            code => PushState::Unknown(code),
+        })
+    }
+}
+
+/// 推送任务详情
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushDetail {
+    /// 推送批次 ID
+    pub batch_id: String,
+    /// 推送任务工作号
+    #[serde(default)]
+    pub workno: Option<String>,
+    /// 推送任务状态
+    pub status: PushState,
+    /// 推送目标设备数量
+    #[serde(default)]
+    pub target_count: u64,
+    /// 推送任务创建时间, 毫秒时间戳
+    #[serde(default)]
+    pub create_time: Option<u64>,
+    /// 定时推送时间, 毫秒时间戳
+    #[serde(default)]
+    pub task_time: Option<u64>,
+}
+
+/// 待发送的定时推送任务列表
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduledPushes {
+    /// 当前页的推送任务
+    pub list: Vec<PushDetail>,
+    /// 待发送的定时推送任务总数
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchId<'r> {
+    batch_id: &'r str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Page {
+    page_index: u32,
+    page_size: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplacePush<'r> {
+    batch_id: &'r str,
+    push_notify: PushNotify<'r>,
+    push_forward: Forward,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) enum PushQuery<'r> {
+    BatchId(&'r str),
+    Workno(&'r str),
+}
+
+impl<'r> PushQuery<'r> {
+    pub(super) fn path(
+        &self,
+        batch_id_path: &'static str,
+        workno_path: &'static str,
+    ) -> &'static str {
+        match self {
+            PushQuery::BatchId(_) => batch_id_path,
+            PushQuery::Workno(_) => workno_path,
+        }
+    }
+}
+
+/// 已创建推送的查询与管理客户端
+pub struct PushApiClient<C: PushClient> {
+    pub(super) client: C,
+    pub(super) config: ConfigRef,
+}
+
+impl<C: PushClient> PushApiClient<C> {
+    /// 使用指定的配置创建客户端
+    ///
+    /// 可通过 [`global_config`](crate::global_config) 使用全局配置
+    pub fn new(client: C, config: impl Into<Arc<MobPushConfig>>) -> Self {
+        Self {
+            client,
+            config: ConfigRef::new(config),
+        }
+    }
+
+    async fn query(&self, query: PushQuery<'_>) -> Result<Option<PushDetail>, ApiError<C>> {
+        let path = query.path("/v3/push/getByBatchId", "/v3/push/getByWorkno");
+        post_signed(&self.client, self.config.get(), path, &query).await
+    }
+
+    /// 根据推送批次 ID 查询推送任务
+    pub async fn query_by_batch_id(
+        &self,
+        batch_id: &str,
+    ) -> Result<Option<PushDetail>, ApiError<C>> {
+        self.query(PushQuery::BatchId(batch_id)).await
+    }
+
+    /// 根据推送任务工作号查询推送任务
+    pub async fn query_by_workno(&self, workno: &str) -> Result<Option<PushDetail>, ApiError<C>> {
+        self.query(PushQuery::Workno(workno)).await
+    }
+
+    /// 分页查询尚未发送的定时推送任务, `page_index` 从 1 开始
+    ///
+    /// 可通过 [`PushApiClient::cancel`] 取消其中的任务
+    pub async fn list_scheduled(
+        &self,
+        page_index: u32,
+        page_size: u32,
+    ) -> Result<ScheduledPushes, ApiError<C>> {
+        let body = Page {
+            page_index,
+            page_size,
+        };
+        post_signed(
+            &self.client,
+            self.config.get(),
+            "/v3/push/listTimedTask",
+            &body,
+        )
+        .await
+        .map(Option::unwrap_or_default)
+    }
+
+    async fn batch_operate(&self, path: &str, batch_id: &str) -> Result<(), ApiError<C>> {
+        post_signed::<_, _, serde_json::Value>(
+            &self.client,
+            self.config.get(),
+            path,
+            &BatchId { batch_id },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// 取消尚未发送的定时推送
+    pub async fn cancel(&self, batch_id: &str) -> Result<(), ApiError<C>> {
+        self.batch_operate("/v3/push/cancel", batch_id).await
+    }
+
+    /// 撤回已送达的推送通知
+    pub async fn recall(&self, batch_id: &str) -> Result<(), ApiError<C>> {
+        self.batch_operate("/v3/push/drop", batch_id).await
+    }
+
+    /// 使用新的推送消息替换已发送的推送通知内容
+    ///
+    /// 仅替换标题、正文、通知配置与跳转方式, 推送目标保持不变。
+    /// 替换内容总是以通知消息类型发送
+    pub async fn replace<T: PushEntity>(
+        &self,
+        batch_id: &str,
+        data: &T,
+    ) -> Result<(), ApiError<C>> {
+        let mut push_notify =
+            PushNotify::new_with_builder(data, NotifyType::Notify, data.platforms());
+        push_notify.set_ios_production(self.config.get().ios_production);
+        let body = ReplacePush {
+            batch_id,
+            push_notify,
+            push_forward: Forward::new(data),
+        };
+        post_signed::<_, _, serde_json::Value>(
+            &self.client,
+            self.config.get(),
+            "/v3/push/replace",
+            &body,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// 撤回一条消息推送时创建的全部推送批次
+    ///
+    /// 单个批次撤回失败不会中断其余批次的撤回, 全部失败的批次 ID 与异常将一并返回
+    pub async fn recall_all<T>(
+        &self,
+        result: &PushResult<T>,
+    ) -> Result<(), Vec<(String, ApiError<C>)>> {
+        let mut failures = Vec::new();
+        for batch_id in result.batch_ids() {
+            if let Err(err) = self.recall(batch_id).await {
+                failures.push((batch_id.to_owned(), err));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use serde_json::json;
+
+    use crate::{
+        api::ApiError,
+        test_support::{MockResponse, RecordingClient},
+        BatchResult, PushEntity, PushResult,
+    };
+
+    use super::PushApiClient;
+
+    struct Message;
+
+    impl PushEntity for Message {
+        type Resource = i32;
+
+        fn get_resource(&self) -> &Self::Resource {
+            &1
+        }
+
+        type Content = str;
+
+        fn get_send_content(&self) -> &Self::Content {
+            "new body"
+        }
+
+        fn get_title(&self) -> std::borrow::Cow<'_, str> {
+            "new title".into()
+        }
+    }
+
+    fn new_client() -> (RecordingClient, PushApiClient<RecordingClient>) {
+        let recording = RecordingClient::new();
+        let client = PushApiClient::new(recording.clone(), RecordingClient::config());
+        (recording, client)
+    }
+
+    fn push_result(batch_ids: &[Option<&str>]) -> PushResult<Message> {
+        PushResult {
+            data: Message,
+            batches: batch_ids
+                .iter()
+                .map(|batch_id| BatchResult {
+                    batch_id: batch_id.map(str::to_owned),
+                    recipients: Some(1),
+                    sent_at: SystemTime::now(),
+                    elapsed: Duration::ZERO,
+                })
+                .collect(),
+            started_at: SystemTime::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operate() {
+        let (recording, client) = new_client();
+        client.cancel("b1").await.unwrap();
+        client.recall("b2").await.unwrap();
+        client.replace("b3", &Message).await.unwrap();
+
+        let calls = recording.calls();
+        assert!(calls
+            .iter()
+            .all(|call| call.is_signed_by(&RecordingClient::config())));
+        assert_eq!(calls[0].path(), "/v3/push/cancel");
+        assert_eq!(calls[0].body, json!({"batchId": "b1"}));
+        assert_eq!(calls[1].path(), "/v3/push/drop");
+        assert_eq!(calls[1].body, json!({"batchId": "b2"}));
+        assert_eq!(calls[2].path(), "/v3/push/replace");
+        assert_eq!(calls[2].body["batchId"], "b3");
+        assert_eq!(calls[2].body["pushNotify"]["title"], "new title");
+        assert_eq!(calls[2].body["pushNotify"]["content"], "new body");
+
+        recording.script(MockResponse::new_mob(404, "batch not found"));
+        let err = client.cancel("b4").await.unwrap_err();
+        assert!(matches!(err, ApiError::Mob { state: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_recall_all() {
+        let (recording, client) = new_client();
+        recording
+            .script(MockResponse::new_success(""))
+            .script(MockResponse::new_mob(500, "busy"))
+            .script(MockResponse::Transport("connection reset".into()));
+
+        // 缺少批次 ID 的批次被跳过, 单个批次失败不中断其余批次
+        let result = push_result(&[Some("b1"), None, Some("b2"), Some("b3")]);
+        let failures = client.recall_all(&result).await.unwrap_err();
+        let batch_ids = recording
+            .calls()
+            .iter()
+            .map(|call| call.body["batchId"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(batch_ids, ["b1", "b2", "b3"]);
+
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].0, "b2");
+        assert!(matches!(failures[0].1, ApiError::Mob { state: 500, .. }));
+        assert_eq!(failures[1].0, "b3");
+        assert!(matches!(failures[1].1, ApiError::Request(_)));
+
+        recording.reset();
+        client.recall_all(&result).await.unwrap();
+        assert_eq!(recording.calls().len(), 3);
+    }
+}
@@ -0,0 +1,127 @@
+//! 推送送达统计
+
+use serde::Deserialize;
+
+use crate::http_client::PushClient;
+
+use super::{
+    post_signed,
+    push::{PushApiClient, PushQuery},
+    ApiError,
+};
+
+/// 送达统计数据
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeliveryCount {
+    /// 推送目标数量
+    pub target: u64,
+    /// 送达数量
+    pub delivered: u64,
+    /// 展示数量
+    pub displayed: u64,
+    /// 点击数量
+    pub clicked: u64,
+}
+
+/// 厂商通道送达统计
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelStats {
+    /// 厂商通道名称, 如 `xiaomi` `huawei` `apns`
+    pub channel: String,
+    /// 该通道的送达统计
+    #[serde(flatten)]
+    pub count: DeliveryCount,
+}
+
+/// 推送任务的送达统计
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PushStats {
+    /// 全部平台的送达统计
+    pub total: DeliveryCount,
+    /// Android 平台的送达统计
+    pub android: DeliveryCount,
+    /// iOS 平台的送达统计
+    pub ios: DeliveryCount,
+    /// 各厂商通道的送达统计
+    pub channels: Vec<ChannelStats>,
+}
+
+impl<C: PushClient> PushApiClient<C> {
+    async fn stats(&self, query: PushQuery<'_>) -> Result<PushStats, ApiError<C>> {
+        let path = query.path("/v3/stats/getByBatchId", "/v3/stats/getByWorkno");
+        post_signed(&self.client, self.config.get(), path, &query)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    /// 根据推送批次 ID 查询送达统计
+    pub async fn stats_by_batch_id(&self, batch_id: &str) -> Result<PushStats, ApiError<C>> {
+        self.stats(PushQuery::BatchId(batch_id)).await
+    }
+
+    /// 根据推送任务工作号查询送达统计
+    pub async fn stats_by_workno(&self, workno: &str) -> Result<PushStats, ApiError<C>> {
+        self.stats(PushQuery::Workno(workno)).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::{
+        api::push::PushApiClient,
+        test_support::{MockResponse, RecordingClient},
+    };
+
+    use super::PushStats;
+
+    #[tokio::test]
+    async fn test_request() {
+        let recording = RecordingClient::new();
+        let client = PushApiClient::new(recording.clone(), RecordingClient::config());
+        recording.script(MockResponse::Http {
+            status: 200,
+            body: json!({"status": 200, "res": {"total": {"target": 3}}}).to_string(),
+        });
+
+        let stats = client.stats_by_batch_id("b1").await.unwrap();
+        assert_eq!(stats.total.target, 3);
+        // 缺少 res 时返回空的统计
+        let stats = client.stats_by_workno("w1").await.unwrap();
+        assert_eq!(stats.total.target, 0);
+
+        let calls = recording.calls();
+        assert_eq!(calls.len(), 2);
+        for call in &calls {
+            assert_eq!(call.url.host_str(), Some("api.push.mob.com"));
+            assert_eq!(call.header("key"), Some("recording-key"));
+            assert!(call.is_signed_by(&RecordingClient::config()));
+        }
+        assert_eq!(calls[0].path(), "/v3/stats/getByBatchId");
+        assert_eq!(calls[0].body, json!({"batchId": "b1"}));
+        assert_eq!(calls[1].path(), "/v3/stats/getByWorkno");
+        assert_eq!(calls[1].body, json!({"workno": "w1"}));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let stats: PushStats = serde_json::from_str(
+            r#"{
+                "total": {"target": 3, "delivered": 2, "displayed": 2, "clicked": 1},
+                "android": {"target": 2, "delivered": 1},
+                "channels": [{"channel": "xiaomi", "target": 2, "delivered": 1}]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(stats.total.clicked, 1);
+        assert_eq!(stats.android.delivered, 1);
+        assert_eq!(stats.ios.target, 0);
+        assert_eq!(stats.channels[0].channel, "xiaomi");
+        assert_eq!(stats.channels[0].count.target, 2);
+    }
+}
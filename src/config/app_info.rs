@@ -1,10 +1,325 @@
-#[derive(Debug, serde::Deserialize)]
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{api::MOB_API_HOST, push_target::MAX_TARGET_SIZE, RateLimit};
+
+use super::ConfigError;
+
+/// Mob Push 应用配置
+///
+/// 除 `key` 与 `secret` 外均有默认值, 反序列化时检查配置是否有效
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawConfig")]
 pub struct MobPushConfig {
+    /// Mob 应用 app key
     pub key: String,
+    /// Mob 应用 app secret
     pub secret: String,
+    /// Mob Push 接口地址, 可包含路径前缀以通过代理访问
+    pub base_url: url::Url,
+    /// 单次请求的超时时间, 配置文件中以毫秒表示, 默认不限制
+    pub request_timeout: Option<Duration>,
+    /// 推送请求的速率限制, 可被 [`MobPusher::set_rate_limit`](crate::MobPusher::set_rate_limit) 覆盖
+    pub rate_limit: RateLimit,
+    /// 单个推送批次的最大 别名/rid 数量, 不超过 1000
+    pub batch_size: usize,
+    /// iOS 推送是否使用生产环境, 为 `false` 时推送到沙盒环境
+    pub ios_production: bool,
+}
+
+/// 配置文件中未经检查的配置
+#[derive(Debug, Deserialize)]
+struct RawConfig {
+    key: String,
+    secret: String,
+    #[serde(default = "default_base_url")]
+    base_url: url::Url,
+    #[serde(
+        default,
+        rename = "request_timeout_ms",
+        deserialize_with = "deserialize_millis"
+    )]
+    request_timeout: Option<Duration>,
+    #[serde(default)]
+    rate_limit: RateLimit,
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    #[serde(default = "default_ios_production")]
+    ios_production: bool,
+}
+
+impl TryFrom<RawConfig> for MobPushConfig {
+    type Error = ConfigError;
+
+    fn try_from(raw: RawConfig) -> Result<Self, Self::Error> {
+        let cfg = Self {
+            key: raw.key,
+            secret: raw.secret,
+            base_url: raw.base_url,
+            request_timeout: raw.request_timeout,
+            rate_limit: raw.rate_limit,
+            batch_size: raw.batch_size,
+            ios_production: raw.ios_production,
+        };
+        cfg.validate()?;
+        Ok(cfg)
+    }
+}
+
+fn default_base_url() -> url::Url {
+    url::Url::parse(MOB_API_HOST).expect("Invalid Mob Push Api Url")
+}
+
+fn default_batch_size() -> usize {
+    MAX_TARGET_SIZE
+}
+
+fn default_ios_production() -> bool {
+    true
+}
+
+fn deserialize_millis<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<u64>::deserialize(deserializer).map(|millis| millis.map(Duration::from_millis))
+}
+
+/// 环境变量名称
+const ENV_KEY: &str = "MOB_PUSH_KEY";
+const ENV_SECRET: &str = "MOB_PUSH_SECRET";
+const ENV_BASE_URL: &str = "MOB_PUSH_BASE_URL";
+const ENV_REQUEST_TIMEOUT: &str = "MOB_PUSH_REQUEST_TIMEOUT_MS";
+const ENV_QPS: &str = "MOB_PUSH_QPS";
+const ENV_BURST: &str = "MOB_PUSH_BURST";
+const ENV_BATCH_SIZE: &str = "MOB_PUSH_BATCH_SIZE";
+const ENV_IOS_PRODUCTION: &str = "MOB_PUSH_IOS_PRODUCTION";
+
+impl MobPushConfig {
+    /// 使用 app key 与 app secret 创建配置, 其余配置使用默认值
+    pub fn new(key: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            secret: secret.into(),
+            base_url: default_base_url(),
+            request_timeout: None,
+            rate_limit: RateLimit::default(),
+            batch_size: default_batch_size(),
+            ios_production: default_ios_production(),
+        }
+    }
+
+    /// 接口的完整地址, 保留 `base_url` 中的路径前缀, 如代理地址 `https://gw/mob/`
+    pub(crate) fn api_url(&self, path: &str) -> url::Url {
+        let mut url = self.base_url.clone();
+        // http 与 https 地址总是可以追加路径
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments
+                .pop_if_empty()
+                .extend(path.split('/').filter(|segment| !segment.is_empty()));
+        }
+        url
+    }
+
+    /// 从配置文件中加载配置
+    ///
+    /// 扩展名为 `json` 时以 JSON 格式解析, 否则以 TOML 格式解析
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json_str(&content),
+            _ => Self::from_toml_str(&content),
+        }
+    }
+
+    /// 从 TOML 文本中加载配置
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        toml::from_str::<RawConfig>(content)?.try_into()
+    }
+
+    /// 从 JSON 文本中加载配置
+    pub fn from_json_str(content: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str::<RawConfig>(content)?.try_into()
+    }
+
+    /// 从较大 TOML 配置的某个表中加载配置, `section` 以 `.` 分隔多层表名
+    ///
+    /// ```toml
+    /// [services.mob_push]
+    /// key = "..."
+    /// secret = "..."
+    /// ```
+    ///
+    /// 对应 `section` 为 `services.mob_push`
+    pub fn from_toml_section(content: &str, section: &str) -> Result<Self, ConfigError> {
+        let mut value: toml::Value = toml::from_str(content)?;
+        for name in section.split('.') {
+            value = match value {
+                toml::Value::Table(mut table) => table.remove(name),
+                _ => None,
+            }
+            .ok_or_else(|| ConfigError::MissingSection(section.to_owned()))?;
+        }
+        value.try_into::<RawConfig>()?.try_into()
+    }
+
+    /// 从较大 JSON 配置的某个对象中加载配置, `section` 以 `.` 分隔多层字段名
+    pub fn from_json_section(content: &str, section: &str) -> Result<Self, ConfigError> {
+        let mut value: serde_json::Value = serde_json::from_str(content)?;
+        for name in section.split('.') {
+            value = value
+                .get_mut(name)
+                .map(serde_json::Value::take)
+                .ok_or_else(|| ConfigError::MissingSection(section.to_owned()))?;
+        }
+        serde_json::from_value::<RawConfig>(value)?.try_into()
+    }
+
+    /// 从环境变量中加载配置
+    ///
+    /// - `MOB_PUSH_KEY` 与 `MOB_PUSH_SECRET` 必须设置
+    /// - `MOB_PUSH_BASE_URL`, `MOB_PUSH_REQUEST_TIMEOUT_MS`, `MOB_PUSH_QPS`,
+    ///   `MOB_PUSH_BURST`, `MOB_PUSH_BATCH_SIZE`, `MOB_PUSH_IOS_PRODUCTION` 可选
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_env_with(|name| std::env::var(name).ok())
+    }
+
+    fn from_env_with(var: impl Fn(&'static str) -> Option<String>) -> Result<Self, ConfigError> {
+        fn parse<T: std::str::FromStr>(
+            name: &'static str,
+            value: Option<String>,
+        ) -> Result<Option<T>, ConfigError> {
+            value
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidEnv { name, value })
+                })
+                .transpose()
+        }
+
+        let key = var(ENV_KEY).ok_or(ConfigError::MissingEnv(ENV_KEY))?;
+        let secret = var(ENV_SECRET).ok_or(ConfigError::MissingEnv(ENV_SECRET))?;
+        let mut cfg = Self::new(key, secret);
+
+        if let Some(base_url) = parse(ENV_BASE_URL, var(ENV_BASE_URL))? {
+            cfg.base_url = base_url;
+        }
+        if let Some(millis) = parse(ENV_REQUEST_TIMEOUT, var(ENV_REQUEST_TIMEOUT))? {
+            cfg.request_timeout = Some(Duration::from_millis(millis));
+        }
+        if let Some(qps) = parse(ENV_QPS, var(ENV_QPS))? {
+            cfg.rate_limit.qps = qps;
+        }
+        if let Some(burst) = parse(ENV_BURST, var(ENV_BURST))? {
+            cfg.rate_limit.burst = burst;
+        }
+        if let Some(batch_size) = parse(ENV_BATCH_SIZE, var(ENV_BATCH_SIZE))? {
+            cfg.batch_size = batch_size;
+        }
+        if let Some(ios_production) = parse(ENV_IOS_PRODUCTION, var(ENV_IOS_PRODUCTION))? {
+            cfg.ios_production = ios_production;
+        }
+
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// 检查配置是否有效
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.key.is_empty() {
+            return Err(ConfigError::Invalid("key must not be empty"));
+        }
+        if self.secret.is_empty() {
+            return Err(ConfigError::Invalid("secret must not be empty"));
+        }
+        if !matches!(self.base_url.scheme(), "http" | "https") {
+            return Err(ConfigError::Invalid("base_url must be a http or https url"));
+        }
+        if self.request_timeout == Some(Duration::ZERO) {
+            return Err(ConfigError::Invalid("request_timeout must be positive"));
+        }
+        self.rate_limit.validate()?;
+        if !(1..=MAX_TARGET_SIZE).contains(&self.batch_size) {
+            return Err(ConfigError::Invalid("batch_size must be in 1..=1000"));
+        }
+        Ok(())
+    }
 }
 
-pub trait MobPushConfigTrait {
-    fn get_key(&self) -> &str;
-    fn get_secret(&self) -> &str;
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::config::ConfigError;
+
+    use super::MobPushConfig;
+
+    #[test]
+    fn test_sources() {
+        let toml = r#"
+            name = "service"
+
+            [services.mob_push]
+            key = "key"
+            secret = "secret"
+            request_timeout_ms = 3000
+            rate_limit = { qps = 5.0, burst = 2 }
+        "#;
+        let cfg = MobPushConfig::from_toml_section(toml, "services.mob_push").unwrap();
+        assert_eq!(cfg.request_timeout, Some(Duration::from_secs(3)));
+        assert_eq!(cfg.rate_limit.burst, 2);
+        assert_eq!(cfg.batch_size, 1000);
+        assert!(matches!(
+            MobPushConfig::from_toml_section(toml, "services.other"),
+            Err(ConfigError::MissingSection(_))
+        ));
+
+        let json = r#"{"mob": {"key": "key", "secret": "secret", "ios_production": false}}"#;
+        let cfg = MobPushConfig::from_json_section(json, "mob").unwrap();
+        assert!(!cfg.ios_production);
+        assert!(matches!(
+            MobPushConfig::from_json_str(r#"{"key": "key", "secret": ""}"#),
+            Err(ConfigError::Invalid(_))
+        ));
+        // 直接反序列化时同样检查配置
+        let invalid = r#"{"key": "key", "secret": "secret", "rate_limit": {"qps": 0.0}}"#;
+        assert!(serde_json::from_str::<MobPushConfig>(invalid).is_err());
+        assert!(toml::from_str::<MobPushConfig>("key = \"key\"\nsecret = \"secret\"").is_ok());
+
+        let env = |name| match name {
+            "MOB_PUSH_KEY" => Some("key".to_owned()),
+            "MOB_PUSH_SECRET" => Some("secret".to_owned()),
+            "MOB_PUSH_BATCH_SIZE" => Some("1001".to_owned()),
+            _ => None,
+        };
+        assert!(matches!(
+            MobPushConfig::from_env_with(env),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            MobPushConfig::from_env_with(|_| None),
+            Err(ConfigError::MissingEnv("MOB_PUSH_KEY"))
+        ));
+    }
+
+    #[test]
+    fn test_api_url() {
+        let mut cfg = MobPushConfig::new("key", "secret");
+        assert_eq!(
+            cfg.api_url("/v3/push/createPush").as_str(),
+            "https://api.push.mob.com/v3/push/createPush"
+        );
+
+        for base_url in ["https://gw/mob/", "https://gw/mob"] {
+            cfg.base_url = base_url.parse().unwrap();
+            assert_eq!(
+                cfg.api_url("/v3/push/createPush").as_str(),
+                "https://gw/mob/v3/push/createPush"
+            );
+        }
+    }
 }
@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+/// 加载配置时的异常
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 读取配置文件异常
+    Io(std::io::Error),
+    /// TOML 解析异常
+    Toml(toml::de::Error),
+    /// JSON 解析异常
+    Json(serde_json::Error),
+    /// 配置中不存在指定的表
+    MissingSection(String),
+    /// 缺少必须的环境变量
+    MissingEnv(&'static str),
+    /// 环境变量的值无法解析
+    InvalidEnv { name: &'static str, value: String },
+    /// 配置的值无效
+    Invalid(&'static str),
+    /// 全局配置已设置
+    AlreadySet,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "Read Config Error : {err}"),
+            ConfigError::Toml(err) => write!(f, "Toml Error : {err}"),
+            ConfigError::Json(err) => write!(f, "Json Error : {err}"),
+            ConfigError::MissingSection(section) => {
+                write!(f, "Config Section `{section}` Not Exist")
+            }
+            ConfigError::MissingEnv(name) => write!(f, "Env Var `{name}` Not Set"),
+            ConfigError::InvalidEnv { name, value } => {
+                write!(f, "Env Var `{name}` Invalid : {value:?}")
+            }
+            ConfigError::Invalid(reason) => write!(f, "Invalid Config : {reason}"),
+            ConfigError::AlreadySet => write!(f, "Global Config Already Set"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Toml(err) => Some(err),
+            ConfigError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        Self::Toml(err)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
@@ -1,38 +1,49 @@
 mod app_info;
+mod error;
+
+use std::sync::Arc;
 
 use once_cell::sync::OnceCell;
 
-pub use self::app_info::MobPushConfig;
+pub use self::{app_info::MobPushConfig, error::ConfigError};
 
-#[allow(dead_code)]
-static PUSHER_CONFIG: OnceCell<MobPushConfig> = OnceCell::new();
+/// 可选的全局配置, 仅用于便捷地在多处共享同一份配置
+static PUSHER_CONFIG: OnceCell<Arc<MobPushConfig>> = OnceCell::new();
 
-#[cfg(test)]
-pub(crate) fn load_from_test() {
-    let app = load_cfg();
-    PUSHER_CONFIG.set(app).expect("Config set")
+fn load_cfg() -> Result<MobPushConfig, ConfigError> {
+    MobPushConfig::from_path("./config.toml")
 }
-fn load_cfg() -> MobPushConfig {
-    use std::{fs, path::Path};
-    let vec = fs::read_to_string(Path::new("./config.toml")).expect("Config info not exist");
-    toml::from_str(&vec).expect("Parse to Toml Failure")
+
+/// 设置全局配置
+///
+/// 全局配置只能设置一次, 已设置时返回传入的配置
+pub fn set_config(cfg: impl Into<Arc<MobPushConfig>>) -> Result<(), Arc<MobPushConfig>> {
+    PUSHER_CONFIG
+        .try_insert(cfg.into())
+        .map(|_| ())
+        .map_err(|(_, cfg)| cfg)
 }
 
-pub fn set_config(cfg: MobPushConfig) {
-    PUSHER_CONFIG.set(cfg).expect("Config Set")
+/// 获取通过 [`set_config`] 设置的全局配置
+pub fn global_config() -> Option<Arc<MobPushConfig>> {
+    PUSHER_CONFIG.get().cloned()
 }
 
-pub(crate) fn get_config() -> &'static MobPushConfig {
-    #[cfg(test)]
-    {
-        PUSHER_CONFIG.get_or_init(load_cfg)
-    }
-    #[cfg(not(test))]
-    {
-        PUSHER_CONFIG.get().expect("Config Not Set")
-    }
+/// 从 `./config.toml` 加载全局配置
+pub fn load_config_from_default() -> Result<(), ConfigError> {
+    set_config(load_cfg()?).map_err(|_| ConfigError::AlreadySet)
 }
 
-pub fn load_config_from_default() {
-    PUSHER_CONFIG.set(load_cfg()).ok();
+/// 推送器与接口客户端使用的配置
+#[derive(Debug, Clone)]
+pub(crate) struct ConfigRef(Arc<MobPushConfig>);
+
+impl ConfigRef {
+    pub(crate) fn new(config: impl Into<Arc<MobPushConfig>>) -> Self {
+        Self(config.into())
+    }
+
+    pub(crate) fn get(&self) -> &MobPushConfig {
+        &self.0
+    }
 }
@@ -1,6 +1,10 @@
 use std::fmt::{Debug, Display};
 
-use crate::{http_client::PushClient, UserSubscribeManage};
+use crate::{
+    api::{ApiError, ErrorKind},
+    http_client::PushClient,
+    PushTarget, UserSubscribeManage,
+};
 
 /// mob push 推送期间的异常
 pub enum MobPushError<M, C>
@@ -10,12 +14,73 @@ where
 {
     /// 用户订阅持久化管理出现的异常
     Manage(M::Err),
+    /// 构建请求时异常, 如请求头或 URL 不合法
+    Build(C::Error),
     /// 发起请求时异常
     Request(C::Error),
     /// json 序列化、反序列化异常
     Json(serde_json::Error),
     /// mob 推送响应异常
     Mob { state: u16, msg: String },
+    /// HTTP 响应状态异常, 响应体已被截断
+    Http { status: u16, body: String },
+    /// 请求超时
+    Timeout,
+    /// 持久化队列读写异常
+    Outbox(Box<dyn std::error::Error + Send + Sync>),
+    /// 单个推送批次在重试后仍然失败, 其余批次不受影响
+    Batch {
+        /// 失败批次的推送目标
+        target: PushTarget,
+        /// 已尝试的次数
+        attempts: u32,
+        /// 最后一次尝试的异常
+        error: Box<Self>,
+    },
+}
+
+impl<M, C> MobPushError<M, C>
+where
+    M: UserSubscribeManage,
+    C: PushClient,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            MobPushError::Manage(_)
+            | MobPushError::Build(_)
+            | MobPushError::Json(_)
+            | MobPushError::Outbox(_) => ErrorKind::Permanent,
+            MobPushError::Request(_) | MobPushError::Timeout => ErrorKind::Transport,
+            MobPushError::Mob { state, .. } | MobPushError::Http { status: state, .. } => {
+                ErrorKind::Status(*state)
+            }
+            MobPushError::Batch { error, .. } => error.kind(),
+        }
+    }
+
+    /// 异常是否为暂时性的, 重试后可能成功
+    ///
+    /// 发送请求异常、请求超时、Mob 限流与服务端异常可重试,
+    /// 构建请求异常等其余异常重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+
+    /// 异常是否为 Mob 限流
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind().is_rate_limited()
+    }
+
+    /// 推送失败的 rid, 仅在按 rid 推送的批次失败时存在
+    pub fn affected_rids(&self) -> Option<&[String]> {
+        match self {
+            MobPushError::Batch {
+                target: PushTarget::Rids(rids),
+                ..
+            } => Some(rids),
+            _ => None,
+        }
+    }
 }
 
 impl<M, C> std::fmt::Debug for MobPushError<M, C>
@@ -27,6 +92,7 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Manage(err) => f.debug_tuple("Manage").field(err).finish(),
+            Self::Build(err) => f.debug_tuple("Build").field(err).finish(),
             Self::Request(err) => f.debug_tuple("Request").field(err).finish(),
             MobPushError::Mob { state, msg } => f
                 .debug_struct("Mob")
@@ -34,6 +100,23 @@ where
                 .field("msg", msg)
                 .finish(),
             MobPushError::Json(err) => f.debug_tuple("Json").field(err).finish(),
+            MobPushError::Http { status, body } => f
+                .debug_struct("Http")
+                .field("status", status)
+                .field("body", body)
+                .finish(),
+            MobPushError::Timeout => f.write_str("Timeout"),
+            MobPushError::Outbox(err) => f.debug_tuple("Outbox").field(err).finish(),
+            MobPushError::Batch {
+                target,
+                attempts,
+                error,
+            } => f
+                .debug_struct("Batch")
+                .field("target", target)
+                .field("attempts", attempts)
+                .field("error", error)
+                .finish(),
         }
     }
 }
@@ -47,9 +130,24 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             MobPushError::Manage(err) => write!(f, "Subscribe Manage Error : {err}"),
+            MobPushError::Build(err) => write!(f, "Build Request Error : {err}"),
             MobPushError::Request(err) => write!(f, "Request Error : {err}"),
             MobPushError::Mob { state, msg } => write!(f, "Mob Pusher Error : [{}] {}", state, msg),
             MobPushError::Json(err) => write!(f, "Json Error : {err}"),
+            MobPushError::Http { status, body } => write!(f, "Http Error : [{status}] {body}"),
+            MobPushError::Timeout => write!(f, "Request Timeout"),
+            MobPushError::Outbox(err) => write!(f, "Outbox Error : {err}"),
+            MobPushError::Batch {
+                target,
+                attempts,
+                error,
+            } => match target.target_size() {
+                Some(size) => write!(
+                    f,
+                    "Push Batch Error : {size} targets after {attempts} attempts, {error}"
+                ),
+                None => write!(f, "Push Batch Error : after {attempts} attempts, {error}"),
+            },
         }
     }
 }
@@ -73,3 +171,16 @@ impl<M: UserSubscribeManage, C: PushClient> From<serde_json::Error> for MobPushE
         Self::Json(err)
     }
 }
+
+impl<M: UserSubscribeManage, C: PushClient> From<ApiError<C>> for MobPushError<M, C> {
+    fn from(err: ApiError<C>) -> Self {
+        match err {
+            ApiError::Build(err) => Self::Build(err),
+            ApiError::Request(err) => Self::Request(err),
+            ApiError::Json(err) => Self::Json(err),
+            ApiError::Mob { state, msg } => Self::Mob { state, msg },
+            ApiError::Http { status, body } => Self::Http { status, body },
+            ApiError::Timeout => Self::Timeout,
+        }
+    }
+}
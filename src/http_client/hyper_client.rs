@@ -0,0 +1,195 @@
+use std::{fmt::Display, time::Duration};
+
+use async_trait::async_trait;
+use hyper::{client::HttpConnector, http, Body, Method, Request, Response};
+use tokio::time::Instant;
+
+use super::{ClientOptions, PushClient, PushRequestBuilder, PushResponse};
+
+/// 基于 hyper 的 [`PushClient`] 的异常
+#[derive(Debug)]
+pub enum HyperClientError {
+    /// 构造请求异常
+    Http(http::Error),
+    /// 发送请求或读取响应异常
+    Hyper(hyper::Error),
+    /// 请求超时
+    Timeout,
+}
+
+impl Display for HyperClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HyperClientError::Http(err) => write!(f, "Http Error : {err}"),
+            HyperClientError::Hyper(err) => write!(f, "Hyper Error : {err}"),
+            HyperClientError::Timeout => write!(f, "Request Timeout"),
+        }
+    }
+}
+
+impl std::error::Error for HyperClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HyperClientError::Http(err) => Some(err),
+            HyperClientError::Hyper(err) => Some(err),
+            HyperClientError::Timeout => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Connector {
+    #[cfg(feature = "hyper-native-tls")]
+    NativeTls(hyper::Client<hyper_tls::HttpsConnector<HttpConnector>>),
+    Rustls(hyper::Client<hyper_rustls::HttpsConnector<HttpConnector>>),
+}
+
+/// 基于 hyper 的 [`PushClient`], 需要启用 `hyper` feature
+///
+/// 使用系统原生 TLS 时需要启用 `hyper-native-tls` feature
+#[derive(Debug, Clone)]
+pub struct HyperClient {
+    client: Connector,
+    request_timeout: Option<Duration>,
+}
+
+impl Default for HyperClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperClient {
+    /// 使用默认连接配置创建客户端
+    pub fn new() -> Self {
+        Self::new_with_options(ClientOptions::default())
+    }
+
+    /// 使用指定的连接配置创建客户端
+    pub fn new_with_options(options: ClientOptions) -> Self {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(options.connect_timeout);
+
+        let mut builder = hyper::Client::builder();
+        builder.pool_idle_timeout(options.pool_idle_timeout);
+        if let Some(max_idle) = options.pool_max_idle_per_host {
+            builder.pool_max_idle_per_host(max_idle);
+        }
+
+        let client = match options.tls {
+            #[cfg(feature = "hyper-native-tls")]
+            super::TlsBackend::NativeTls => Connector::NativeTls(
+                builder.build(hyper_tls::HttpsConnector::new_with_connector(http)),
+            ),
+            // 未启用 hyper-native-tls 时总是使用 rustls
+            _ => Connector::Rustls(
+                builder.build(
+                    hyper_rustls::HttpsConnectorBuilder::new()
+                        .with_webpki_roots()
+                        .https_or_http()
+                        .enable_http1()
+                        .wrap_connector(http),
+                ),
+            ),
+        };
+
+        Self {
+            client,
+            request_timeout: options.request_timeout,
+        }
+    }
+}
+
+pub struct HyperRequestBuilder {
+    builder: http::request::Builder,
+    body: Vec<u8>,
+}
+
+/// 读取响应体同样受请求超时时间的限制
+pub struct HyperResponse {
+    response: Response<Body>,
+    deadline: Option<Instant>,
+}
+
+#[async_trait]
+impl PushClient for HyperClient {
+    type RequestBuilder = HyperRequestBuilder;
+    type Error = HyperClientError;
+
+    fn post(&self, url: impl Into<url::Url>) -> Self::RequestBuilder {
+        HyperRequestBuilder {
+            builder: Request::builder()
+                .method(Method::POST)
+                .uri(url.into().as_str()),
+            body: Vec::new(),
+        }
+    }
+
+    async fn send_request(&self, req: Request<Body>) -> Result<HyperResponse, Self::Error> {
+        let deadline = self
+            .request_timeout
+            .and_then(|timeout| Instant::now().checked_add(timeout));
+        let resp = match &self.client {
+            #[cfg(feature = "hyper-native-tls")]
+            Connector::NativeTls(client) => client.request(req),
+            Connector::Rustls(client) => client.request(req),
+        };
+        let response = with_deadline(deadline, resp).await?;
+        Ok(HyperResponse { response, deadline })
+    }
+}
+
+impl PushRequestBuilder for HyperRequestBuilder {
+    type Error = HyperClientError;
+    type Request = Request<Body>;
+    type Response = HyperResponse;
+
+    fn header(self, key: &'static str, value: &str) -> Self {
+        Self {
+            builder: self.builder.header(key, value),
+            body: self.body,
+        }
+    }
+
+    fn body(self, payload: Vec<u8>) -> Self {
+        Self {
+            builder: self.builder,
+            body: payload,
+        }
+    }
+
+    fn build(self) -> Result<Self::Request, Self::Error> {
+        self.builder
+            .body(Body::from(self.body))
+            .map_err(HyperClientError::Http)
+    }
+}
+
+#[async_trait]
+impl PushResponse for HyperResponse {
+    type Error = HyperClientError;
+
+    fn status(&self) -> u16 {
+        self.response.status().as_u16()
+    }
+
+    async fn bytes(self) -> Result<Vec<u8>, Self::Error> {
+        let body = hyper::body::to_bytes(self.response.into_body());
+        with_deadline(self.deadline, body).await.map(Into::into)
+    }
+}
+
+/// 在期限前完成请求, 未设置期限时不限制
+async fn with_deadline<T>(
+    deadline: Option<Instant>,
+    fut: impl std::future::Future<Output = hyper::Result<T>>,
+) -> Result<T, HyperClientError> {
+    let resp = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, fut)
+            .await
+            .map_err(|_| HyperClientError::Timeout)?,
+        None => fut.await,
+    };
+    resp.map_err(HyperClientError::Hyper)
+}
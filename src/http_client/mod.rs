@@ -1,6 +1,19 @@
+#[cfg(feature = "hyper")]
+mod hyper_client;
+#[cfg(any(feature = "reqwest", feature = "hyper"))]
+mod options;
+#[cfg(feature = "reqwest")]
+mod reqwest_client;
+
 use async_trait::async_trait;
 
-use crate::config::get_config;
+#[cfg(feature = "hyper")]
+pub use self::hyper_client::{HyperClient, HyperClientError, HyperRequestBuilder, HyperResponse};
+#[cfg(any(feature = "reqwest", feature = "hyper"))]
+pub use self::options::{ClientOptions, TlsBackend};
+#[cfg(feature = "reqwest")]
+pub use self::reqwest_client::{ReqwestClient, ReqwestRequestBuilder, ReqwestResponse};
+use crate::MobPushConfig;
 
 #[async_trait]
 pub trait PushClient: Sized {
@@ -20,9 +33,9 @@ pub trait PushRequestBuilder: Sized {
     type Request;
     type Response: PushResponse<Error = Self::Error>;
 
-    fn default_headers(self) -> Self {
+    fn default_headers(self, config: &MobPushConfig) -> Self {
         self.header("content-type", "application/json")
-            .header("key", &get_config().key)
+            .header("key", &config.key)
     }
 
     fn header(self, key: &'static str, value: &str) -> Self;
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use typed_builder::TypedBuilder;
+
+/// 内置 [`PushClient`](super::PushClient) 使用的 TLS 实现
+///
+/// 默认使用 rustls, 启用 `native-tls` feature 后默认使用系统原生 TLS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TlsBackend {
+    /// 系统原生 TLS, 需要启用 `native-tls` feature,
+    /// [`HyperClient`](super::HyperClient) 还需要启用 `hyper-native-tls` feature, 否则使用 rustls
+    #[cfg(feature = "native-tls")]
+    #[default]
+    NativeTls,
+    /// rustls
+    #[cfg_attr(not(feature = "native-tls"), default)]
+    Rustls,
+}
+
+/// 内置 [`PushClient`](super::PushClient) 的连接配置
+///
+/// 未设置的配置使用对应 http 客户端的默认值
+#[derive(Debug, Clone, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct ClientOptions {
+    /// 建立连接的超时时间
+    pub(crate) connect_timeout: Option<Duration>,
+    /// 单次请求的超时时间
+    pub(crate) request_timeout: Option<Duration>,
+    /// 连接池中空闲连接的保留时间
+    pub(crate) pool_idle_timeout: Option<Duration>,
+    /// 连接池中每个域名保留的最大空闲连接数
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    /// TLS 实现
+    #[builder(setter(!strip_option))]
+    pub(crate) tls: TlsBackend,
+}
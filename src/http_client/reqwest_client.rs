@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+
+use super::{ClientOptions, PushClient, PushRequestBuilder, PushResponse, TlsBackend};
+
+/// 基于 reqwest 的 [`PushClient`], 需要启用 `reqwest` feature
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestClient(reqwest::Client);
+
+impl ReqwestClient {
+    /// 使用默认连接配置创建客户端
+    pub fn new() -> Result<Self, reqwest::Error> {
+        Self::new_with_options(ClientOptions::default())
+    }
+
+    /// 使用指定的连接配置创建客户端
+    pub fn new_with_options(options: ClientOptions) -> Result<Self, reqwest::Error> {
+        let mut builder = reqwest::Client::builder().pool_idle_timeout(options.pool_idle_timeout);
+        if let Some(timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = options.request_timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(max_idle) = options.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        builder = match options.tls {
+            #[cfg(feature = "native-tls")]
+            TlsBackend::NativeTls => builder.use_native_tls(),
+            TlsBackend::Rustls => builder.use_rustls_tls(),
+        };
+
+        builder.build().map(Self)
+    }
+}
+
+impl From<reqwest::Client> for ReqwestClient {
+    fn from(client: reqwest::Client) -> Self {
+        Self(client)
+    }
+}
+
+pub struct ReqwestRequestBuilder(reqwest::RequestBuilder);
+
+pub struct ReqwestResponse(reqwest::Response);
+
+#[async_trait]
+impl PushClient for ReqwestClient {
+    type RequestBuilder = ReqwestRequestBuilder;
+    type Error = reqwest::Error;
+
+    fn post(&self, url: impl Into<url::Url>) -> Self::RequestBuilder {
+        ReqwestRequestBuilder(self.0.post(url.into()))
+    }
+
+    async fn send_request(&self, req: reqwest::Request) -> Result<ReqwestResponse, Self::Error> {
+        self.0.execute(req).await.map(ReqwestResponse)
+    }
+}
+
+impl PushRequestBuilder for ReqwestRequestBuilder {
+    type Error = reqwest::Error;
+    type Request = reqwest::Request;
+    type Response = ReqwestResponse;
+
+    fn header(self, key: &'static str, value: &str) -> Self {
+        Self(self.0.header(key, value))
+    }
+
+    fn body(self, payload: Vec<u8>) -> Self {
+        Self(self.0.body(payload))
+    }
+
+    fn build(self) -> Result<Self::Request, Self::Error> {
+        self.0.build()
+    }
+}
+
+#[async_trait]
+impl PushResponse for ReqwestResponse {
+    type Error = reqwest::Error;
+
+    fn status(&self) -> u16 {
+        self.0.status().as_u16()
+    }
+
+    async fn bytes(self) -> Result<Vec<u8>, Self::Error> {
+        self.0.bytes().await.map(Into::into)
+    }
+}
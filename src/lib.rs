@@ -1,18 +1,36 @@
+pub mod api;
 mod config;
 mod error;
 pub mod http_client;
+mod message_kind;
+mod platform;
 mod push_forward;
 pub mod push_notify;
+mod push_schedule;
+mod push_target;
 mod pusher;
+mod quiet_hours;
 
 mod pushing_data;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 mod user_subscribe;
 
 pub use pushing_data::PushEntity;
 pub use user_subscribe::{SubscribeFilter, UserMobId, UserSubscribeManage};
 
-pub use config::{load_config_from_default, set_config, MobPushConfig};
+pub use config::{global_config, load_config_from_default, set_config, ConfigError, MobPushConfig};
 
+pub use api::ApiError;
 pub use error::MobPushError;
+pub use message_kind::MessageKind;
+pub use platform::{Platform, Platforms};
 pub use push_forward::{PushForward, Scheme};
-pub use pusher::MobPusher;
+pub use push_schedule::Schedule;
+pub use push_target::{Area, PushTarget, TagsCombine};
+pub use pusher::{
+    BatchResult, ErrorRing, ErrorSink, FileOutbox, InterruptedPush, MobPusher, Outbox, OutboxEntry,
+    OutboxId, PlannedBatch, PlannedDelivery, PushResult, RateLimit, RetryPolicy, ShutdownHandle,
+    ShutdownSummary, SubscribeCheck, SubscribeCheckMode, UnsentBatch,
+};
+pub use quiet_hours::{QuietHours, QuietHoursMode, QuietZone};
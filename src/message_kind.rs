@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+/// 推送消息类型
+#[derive(Debug, Clone, Default)]
+pub enum MessageKind {
+    /// 通知消息, 由系统通知栏展示
+    #[default]
+    Notify,
+    /// 透传消息, 不展示通知, 由客户端自行处理携带的键值对
+    ///
+    /// ## Notify
+    /// - 安卓端的 `Badge` `Image` `NotifyStyle` 配置将被忽略
+    /// - 与 [`Extras`](crate::push_notify::Extras) 中相同的键以此处为准
+    Passthrough(BTreeMap<String, String>),
+    /// 同时推送通知消息与透传消息, 每一批次推送目标将创建两次推送
+    Both(BTreeMap<String, String>),
+}
+
+impl MessageKind {
+    /// 创建一个 **通知** 消息类型
+    pub fn new_notify() -> Self {
+        Self::Notify
+    }
+
+    /// 创建一个 **透传** 消息类型
+    pub fn new_passthrough<I, K, V>(payload: I) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        Self::Passthrough(collect_payload(payload))
+    }
+
+    /// 创建一个 **通知与透传** 消息类型
+    pub fn new_both<I, K, V>(payload: I) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        Self::Both(collect_payload(payload))
+    }
+
+    /// 每一批次推送目标需要创建的推送类型
+    pub(crate) fn notify_types(&self) -> Vec<NotifyType<'_>> {
+        match self {
+            MessageKind::Notify => vec![NotifyType::Notify],
+            MessageKind::Passthrough(payload) => vec![NotifyType::Passthrough(payload)],
+            MessageKind::Both(payload) => {
+                vec![NotifyType::Notify, NotifyType::Passthrough(payload)]
+            }
+        }
+    }
+
+    /// 获取指定的推送类型, 消息类型中不包含该推送类型时为 `None`
+    pub(crate) fn notify_type(&self, passthrough: bool) -> Option<NotifyType<'_>> {
+        self.notify_types()
+            .into_iter()
+            .find(|notify_type| notify_type.is_passthrough() == passthrough)
+    }
+}
+
+fn collect_payload<I, K, V>(payload: I) -> BTreeMap<String, String>
+where
+    K: Into<String>,
+    V: Into<String>,
+    I: IntoIterator<Item = (K, V)>,
+{
+    payload
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect()
+}
+
+/// 单次推送请求的消息类型
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum NotifyType<'p> {
+    Notify,
+    Passthrough(&'p BTreeMap<String, String>),
+}
+
+impl<'p> NotifyType<'p> {
+    pub(crate) fn to_code(self) -> i32 {
+        match self {
+            NotifyType::Notify => 1,
+            NotifyType::Passthrough(_) => 2,
+        }
+    }
+
+    pub(crate) fn is_passthrough(self) -> bool {
+        matches!(self, NotifyType::Passthrough(_))
+    }
+}
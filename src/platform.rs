@@ -0,0 +1,181 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::UserMobId;
+
+/// 推送平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Platform {
+    /// 1 安卓
+    Android,
+    /// 2 iOS
+    Ios,
+    /// 3 鸿蒙
+    HarmonyOs,
+}
+
+impl Platform {
+    pub(crate) fn to_code(self) -> i32 {
+        match self {
+            Platform::Android => 1,
+            Platform::Ios => 2,
+            Platform::HarmonyOs => 3,
+        }
+    }
+
+    pub(crate) fn from_code(code: i32) -> Option<Self> {
+        match code {
+            1 => Some(Platform::Android),
+            2 => Some(Platform::Ios),
+            3 => Some(Platform::HarmonyOs),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for Platform {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(self.to_code())
+    }
+}
+
+/// 推送平台集合
+///
+/// 默认为 安卓 与 iOS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Platforms(BTreeSet<Platform>);
+
+impl Default for Platforms {
+    fn default() -> Self {
+        Self::new([Platform::Android, Platform::Ios])
+    }
+}
+
+impl Platforms {
+    pub fn new(platforms: impl IntoIterator<Item = Platform>) -> Self {
+        Self(platforms.into_iter().collect())
+    }
+
+    /// 仅推送到指定平台
+    pub fn only(platform: Platform) -> Self {
+        Self::new([platform])
+    }
+
+    pub fn insert(&mut self, platform: Platform) -> &mut Self {
+        self.0.insert(platform);
+        self
+    }
+
+    pub fn remove(&mut self, platform: Platform) -> &mut Self {
+        self.0.remove(&platform);
+        self
+    }
+
+    pub fn contains(&self, platform: Platform) -> bool {
+        self.0.contains(&platform)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Platform> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl From<Platform> for Platforms {
+    fn from(platform: Platform) -> Self {
+        Self::only(platform)
+    }
+}
+
+impl FromIterator<Platform> for Platforms {
+    fn from_iter<T: IntoIterator<Item = Platform>>(iter: T) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// 按照用户设备平台将用户分组
+///
+/// - 提供了平台的用户按平台分组, 仅推送到该平台
+/// - 未提供平台的用户推送到全部 `platforms`
+/// - 平台不在 `platforms` 中的用户将被跳过
+pub(crate) fn split_by_platform<U: UserMobId>(
+    users: Vec<U>,
+    platforms: &Platforms,
+) -> Vec<(Platforms, Vec<U>)> {
+    let mut unknown = Vec::new();
+    let mut groups = platforms
+        .iter()
+        .map(|platform| (platform, Vec::new()))
+        .collect::<Vec<_>>();
+
+    for user in users {
+        match user.platform() {
+            None => unknown.push(user),
+            Some(platform) => {
+                if let Some((_, group)) = groups.iter_mut().find(|(p, _)| *p == platform) {
+                    group.push(user)
+                }
+            }
+        }
+    }
+
+    std::iter::once((platforms.clone(), unknown))
+        .chain(
+            groups
+                .into_iter()
+                .map(|(platform, group)| (Platforms::only(platform), group)),
+        )
+        .filter(|(_, group)| !group.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::UserMobId;
+
+    use super::{split_by_platform, Platform, Platforms};
+
+    struct User(&'static str, Option<Platform>);
+
+    impl UserMobId for User {
+        type MobId = &'static str;
+
+        fn get_mob_id(&self) -> Self::MobId {
+            self.0
+        }
+
+        fn platform(&self) -> Option<Platform> {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let users = vec![
+            User("a", Some(Platform::Android)),
+            User("b", None),
+            User("c", Some(Platform::HarmonyOs)),
+            User("d", Some(Platform::Android)),
+        ];
+
+        let groups = split_by_platform(users, &Platforms::default())
+            .into_iter()
+            .map(|(plats, users)| (plats, users.iter().map(|u| u.0).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            groups,
+            [
+                (Platforms::default(), vec!["b"]),
+                (Platforms::only(Platform::Android), vec!["a", "d"]),
+            ]
+        );
+    }
+}
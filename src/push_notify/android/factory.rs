@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use typed_builder::TypedBuilder;
+
+/// 安卓厂商通道的附加配置
+///
+/// 未配置的厂商通道使用 Mob 的默认配置
+#[derive(Debug, Default, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct FactoryExtra {
+    /// 小米通道配置
+    #[serde(rename = "xiaomiExtra", skip_serializing_if = "Option::is_none")]
+    xiaomi: Option<XiaomiExtra>,
+
+    /// 华为通道配置
+    #[serde(rename = "huaweiExtra", skip_serializing_if = "Option::is_none")]
+    huawei: Option<HuaweiExtra>,
+
+    /// OPPO 通道配置
+    #[serde(rename = "oppoExtra", skip_serializing_if = "Option::is_none")]
+    oppo: Option<OppoExtra>,
+
+    /// vivo 通道配置
+    #[serde(rename = "vivoExtra", skip_serializing_if = "Option::is_none")]
+    vivo: Option<VivoExtra>,
+
+    /// 荣耀通道配置
+    #[serde(rename = "honorExtra", skip_serializing_if = "Option::is_none")]
+    honor: Option<HonorExtra>,
+
+    /// 魅族通道配置
+    #[serde(rename = "meizuExtra", skip_serializing_if = "Option::is_none")]
+    meizu: Option<MeizuExtra>,
+}
+
+impl FactoryExtra {
+    pub fn set_xiaomi(&mut self, xiaomi: XiaomiExtra) -> &mut Self {
+        self.xiaomi.replace(xiaomi);
+        self
+    }
+    pub fn set_huawei(&mut self, huawei: HuaweiExtra) -> &mut Self {
+        self.huawei.replace(huawei);
+        self
+    }
+    pub fn set_oppo(&mut self, oppo: OppoExtra) -> &mut Self {
+        self.oppo.replace(oppo);
+        self
+    }
+    pub fn set_vivo(&mut self, vivo: VivoExtra) -> &mut Self {
+        self.vivo.replace(vivo);
+        self
+    }
+    pub fn set_honor(&mut self, honor: HonorExtra) -> &mut Self {
+        self.honor.replace(honor);
+        self
+    }
+    pub fn set_meizu(&mut self, meizu: MeizuExtra) -> &mut Self {
+        self.meizu.replace(meizu);
+        self
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.xiaomi.is_none()
+            && self.huawei.is_none()
+            && self.oppo.is_none()
+            && self.vivo.is_none()
+            && self.honor.is_none()
+            && self.meizu.is_none()
+    }
+}
+
+/// 小米通道配置
+#[derive(Debug, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option, into)))]
+pub struct XiaomiExtra {
+    /// 小米推送通道 ID, 需在小米开放平台申请
+    #[serde(rename = "channelId", skip_serializing_if = "Option::is_none")]
+    channel_id: Option<String>,
+}
+
+/// 消息重要程度
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum Importance {
+    /// 服务与通讯类消息
+    #[serde(rename = "NORMAL")]
+    Normal,
+    /// 资讯营销类消息
+    #[serde(rename = "LOW")]
+    Low,
+}
+
+/// 华为通道配置
+#[derive(Debug, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct HuaweiExtra {
+    /// 消息重要程度
+    #[serde(rename = "importance", skip_serializing_if = "Option::is_none")]
+    importance: Option<Importance>,
+
+    /// 消息自分类标识, 如 `SUBSCRIPTION`
+    #[serde(rename = "category", skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into))]
+    category: Option<String>,
+}
+
+/// OPPO 通道配置
+#[derive(Debug, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct OppoExtra {
+    /// OPPO 推送通道 ID
+    #[serde(rename = "channelId", skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into))]
+    channel_id: Option<String>,
+
+    /// 私信模板 ID
+    #[serde(
+        rename = "privateMsgTemplateId",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[builder(setter(into))]
+    private_msg_template_id: Option<String>,
+
+    /// 私信模板标题参数
+    #[serde(
+        rename = "privateTitleParameters",
+        skip_serializing_if = "Option::is_none"
+    )]
+    private_title_parameters: Option<BTreeMap<String, String>>,
+
+    /// 私信模板内容参数
+    #[serde(
+        rename = "privateContentParameters",
+        skip_serializing_if = "Option::is_none"
+    )]
+    private_content_parameters: Option<BTreeMap<String, String>>,
+}
+
+/// vivo 消息分类
+#[derive(Debug, Clone, Copy)]
+pub enum VivoClassification {
+    /// 0 运营消息
+    Operation,
+    /// 1 系统消息
+    System,
+}
+
+impl Serialize for VivoClassification {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(match self {
+            VivoClassification::Operation => 0,
+            VivoClassification::System => 1,
+        })
+    }
+}
+
+/// vivo 通道配置
+#[derive(Debug, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct VivoExtra {
+    /// 消息分类
+    #[serde(rename = "classification", skip_serializing_if = "Option::is_none")]
+    classification: Option<VivoClassification>,
+}
+
+/// 荣耀通道配置
+#[derive(Debug, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct HonorExtra {
+    /// 消息重要程度
+    #[serde(rename = "importance", skip_serializing_if = "Option::is_none")]
+    importance: Option<Importance>,
+}
+
+/// 魅族消息类型
+#[derive(Debug, Clone, Copy)]
+pub enum MeizuNoticeType {
+    /// 0 公信消息
+    Public,
+    /// 1 私信消息
+    Private,
+}
+
+impl Serialize for MeizuNoticeType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i32(match self {
+            MeizuNoticeType::Public => 0,
+            MeizuNoticeType::Private => 1,
+        })
+    }
+}
+
+/// 魅族通道配置
+#[derive(Debug, Serialize, TypedBuilder, Clone)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct MeizuExtra {
+    /// 消息类型
+    #[serde(rename = "noticeMsgType", skip_serializing_if = "Option::is_none")]
+    notice_msg_type: Option<MeizuNoticeType>,
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{
+        FactoryExtra, HuaweiExtra, Importance, VivoClassification, VivoExtra, XiaomiExtra,
+    };
+
+    #[test]
+    fn test_serde() {
+        let extra = FactoryExtra::builder()
+            .xiaomi(XiaomiExtra::builder().channel_id("cookie").build())
+            .huawei(
+                HuaweiExtra::builder()
+                    .importance(Importance::Normal)
+                    .category("SUBSCRIPTION")
+                    .build(),
+            )
+            .vivo(
+                VivoExtra::builder()
+                    .classification(VivoClassification::System)
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(
+            serde_json::to_value(extra).unwrap(),
+            json!({
+                "xiaomiExtra": {"channelId": "cookie"},
+                "huaweiExtra": {"importance": "NORMAL", "category": "SUBSCRIPTION"},
+                "vivoExtra": {"classification": 1}
+            })
+        );
+    }
+}
@@ -1,8 +1,10 @@
+pub mod factory;
 pub mod notify_style;
 use typed_builder::TypedBuilder;
 
 pub use self::{
     badge::Badge,
+    factory::FactoryExtra,
     image::Image,
     notify_style::NotifyStyle,
     sound::{Sound, Warn},
@@ -22,6 +24,8 @@ pub struct AndroidNotify {
     image: Option<Image>,
     sound: Option<Sound>,
     warn: Option<Warn>,
+    /// 厂商通道配置, 序列化于推送请求的 `pushFactoryExtra`
+    factory_extra: Option<FactoryExtra>,
 }
 
 impl AndroidNotify {
@@ -45,6 +49,33 @@ impl AndroidNotify {
         self.warn.replace(warn);
         self
     }
+    pub fn set_factory_extra(&mut self, factory_extra: FactoryExtra) -> &mut Self {
+        self.factory_extra.replace(factory_extra);
+        self
+    }
+
+    pub(crate) fn factory_extra(&self) -> Option<&FactoryExtra> {
+        self.factory_extra
+            .as_ref()
+            .filter(|extra| !extra.is_empty())
+    }
+
+    /// 移除透传消息不支持的配置
+    pub(crate) fn passthrough_only(&self) -> Self {
+        Self {
+            notify_style: None,
+            badge: None,
+            image: None,
+            ..self.clone()
+        }
+    }
+
+    /// 静默通知, 不播放提示音与震动, 仅保留指示灯提醒
+    pub(crate) fn mute(&mut self) -> &mut Self {
+        self.sound = None;
+        self.warn = Some(sound::WarnSound::IndicatorLight.into());
+        self
+    }
 }
 
 impl SerializeInformation for AndroidNotify {
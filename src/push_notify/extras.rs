@@ -0,0 +1,242 @@
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::Display,
+};
+
+use serde::{ser::SerializeStruct, Serialize};
+
+/// 推送附加的自定义键值对, 客户端可在点击通知时获取
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extras(BTreeMap<String, String>);
+
+/// 自定义键值对超出 Mob 限制
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtrasError {
+    /// 键为空
+    EmptyKey,
+    /// 键值对数量超出限制
+    TooManyEntries { limit: usize },
+    /// 键值对总长度超出限制
+    TooLarge { size: usize, limit: usize },
+}
+
+impl Display for ExtrasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtrasError::EmptyKey => write!(f, "Extras key is empty"),
+            ExtrasError::TooManyEntries { limit } => {
+                write!(f, "Extras entries exceed the limit of {limit}")
+            }
+            ExtrasError::TooLarge { size, limit } => {
+                write!(f, "Extras size {size} exceeds the limit of {limit} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtrasError {}
+
+impl Extras {
+    /// 键值对的最大数量
+    ///
+    /// 见 Mob 推送 REST API 文档 `createPush` 接口中 `pushNotify.extrasMapList` 的字段说明
+    pub const MAX_ENTRIES: usize = 20;
+    /// 全部键值对 UTF-8 编码的最大总长度, 来源同 [`Extras::MAX_ENTRIES`]
+    pub const MAX_SIZE: usize = 1024;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个键值对, 已存在的键将被覆盖
+    ///
+    /// 添加后超出 Mob 限制时不做修改并返回异常
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<&mut Self, ExtrasError> {
+        let (key, value) = (key.into(), value.into());
+        if key.is_empty() {
+            return Err(ExtrasError::EmptyKey);
+        }
+
+        let replaced = self.0.get(&key);
+        let len = self.0.len() + usize::from(replaced.is_none());
+        let size =
+            self.size() - replaced.map_or(0, |old| key.len() + old.len()) + key.len() + value.len();
+        check_limit(len, size)?;
+
+        self.0.insert(key, value);
+        Ok(self)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, String, String> {
+        self.0.iter()
+    }
+
+    /// 全部键值对 UTF-8 编码的总长度
+    pub fn size(&self) -> usize {
+        self.0.iter().map(|(k, v)| k.len() + v.len()).sum()
+    }
+}
+
+impl<'e> IntoIterator for &'e Extras {
+    type Item = (&'e String, &'e String);
+    type IntoIter = btree_map::Iter<'e, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+fn check_limit(len: usize, size: usize) -> Result<(), ExtrasError> {
+    if len > Extras::MAX_ENTRIES {
+        return Err(ExtrasError::TooManyEntries {
+            limit: Extras::MAX_ENTRIES,
+        });
+    }
+    if size > Extras::MAX_SIZE {
+        return Err(ExtrasError::TooLarge {
+            size,
+            limit: Extras::MAX_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// 序列化为 Mob 的 `extrasMapList` 格式
+pub(crate) struct ExtrasMapList<'p>(pub(crate) BTreeMap<&'p str, &'p str>);
+
+impl<'p> ExtrasMapList<'p> {
+    pub(crate) fn new(extras: &'p Extras) -> Self {
+        let mut list = Self(BTreeMap::new());
+        list.extend(extras);
+        list
+    }
+
+    /// 合并键值对, 相同的键以新值为准
+    pub(crate) fn extend<I>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = (&'p String, &'p String)>,
+    {
+        self.0
+            .extend(entries.into_iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 检查合并后的键值对是否超出 Mob 限制
+    ///
+    /// 透传消息的内容也通过 `extrasMapList` 发送, 需要与 [`Extras`] 一并计算
+    pub(crate) fn check(&self) -> Result<(), ExtrasError> {
+        if self.0.keys().any(|k| k.is_empty()) {
+            return Err(ExtrasError::EmptyKey);
+        }
+        let size = self.0.iter().map(|(k, v)| k.len() + v.len()).sum();
+        check_limit(self.0.len(), size)
+    }
+}
+
+impl<'p> Serialize for ExtrasMapList<'p> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        struct Entry<'e>(&'e str, &'e str);
+
+        impl<'e> Serialize for Entry<'e> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let mut entry = serializer.serialize_struct("extrasMap", 2)?;
+                entry.serialize_field("key", self.0)?;
+                entry.serialize_field("value", self.1)?;
+                entry.end()
+            }
+        }
+
+        serializer.collect_seq(self.0.iter().map(|(k, v)| Entry(k, v)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{Extras, ExtrasError, ExtrasMapList};
+
+    #[test]
+    fn test_limit() {
+        let mut extras = Extras::new();
+        for i in 0..Extras::MAX_ENTRIES {
+            extras.insert(i.to_string(), "v").unwrap();
+        }
+
+        assert_eq!(
+            extras.insert("more", "v").unwrap_err(),
+            ExtrasError::TooManyEntries {
+                limit: Extras::MAX_ENTRIES
+            }
+        );
+        // 覆盖已有的键不增加数量
+        extras.insert("0", "value").unwrap();
+        assert_eq!(extras.get("0"), Some("value"));
+
+        let size = extras.size();
+        assert!(matches!(
+            extras.insert("1", "v".repeat(Extras::MAX_SIZE)),
+            Err(ExtrasError::TooLarge { .. })
+        ));
+        assert_eq!(extras.size(), size);
+        assert_eq!(extras.insert("", "v").unwrap_err(), ExtrasError::EmptyKey);
+    }
+
+    #[test]
+    fn test_merged_limit() {
+        let mut extras = Extras::new();
+        extras.insert("key", "v").unwrap();
+        let payload = (0..Extras::MAX_ENTRIES)
+            .map(|i| (i.to_string(), "v".to_owned()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut list = ExtrasMapList::new(&extras);
+        list.check().unwrap();
+        list.extend(&payload);
+        assert_eq!(
+            list.check().unwrap_err(),
+            ExtrasError::TooManyEntries {
+                limit: Extras::MAX_ENTRIES
+            }
+        );
+
+        let payload = BTreeMap::from([("key".to_owned(), "v".repeat(Extras::MAX_SIZE))]);
+        let mut list = ExtrasMapList::new(&extras);
+        list.extend(&payload);
+        assert!(matches!(list.check(), Err(ExtrasError::TooLarge { .. })));
+
+        let payload = BTreeMap::from([(String::new(), "v".to_owned())]);
+        let mut list = ExtrasMapList::new(&extras);
+        list.extend(&payload);
+        assert_eq!(list.check().unwrap_err(), ExtrasError::EmptyKey);
+    }
+}
@@ -65,6 +65,23 @@ impl IosNotify {
         self.sound.replace(sound);
         self
     }
+    /// 静默通知, 不播放声音
+    pub(crate) fn mute(&mut self) -> &mut Self {
+        self.sound.replace(IosPushSound::None);
+        self
+    }
+    /// 移除透传消息不支持的配置
+    ///
+    /// 仅保留 `category` 与 `content-available`
+    pub(crate) fn passthrough_only(&self) -> Self {
+        Self {
+            badge: None,
+            sound: None,
+            subtitle: None,
+            rich_text: None,
+            ..self.clone()
+        }
+    }
     pub fn set_subtitle(&mut self, subtitle: Subtitle) -> &mut Self {
         self.subtitle.replace(subtitle);
         self
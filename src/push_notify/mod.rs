@@ -1,8 +1,11 @@
 pub mod android;
+pub mod extras;
 pub mod ios;
 use serde::{ser::SerializeStruct, Serialize, Serializer};
 use std::ops::{Deref, DerefMut};
 
+pub use self::extras::{Extras, ExtrasError};
+
 pub trait NotifySerialize {
     fn serialize_field(&self) -> usize;
     fn serialize<S: Serializer>(
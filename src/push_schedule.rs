@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use serde::ser::SerializeStruct;
+
+use crate::push_notify::NotifySerialize;
+
+/// 定时推送时间
+///
+/// 保留了创建时的时区信息, 序列化时转换为毫秒时间戳
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule(DateTime<FixedOffset>);
+
+impl Schedule {
+    /// 在指定时间推送, 时间不晚于当前时间时返回 `None`
+    pub fn new_at<Tz: TimeZone>(time: DateTime<Tz>) -> Option<Self> {
+        (time > Utc::now()).then(|| Self(time.fixed_offset()))
+    }
+
+    /// 在当前时间之后 `delay` 推送, `delay` 为零或推送时间溢出时返回 `None`
+    pub fn new_after(delay: Duration) -> Option<Self> {
+        let delay = chrono::Duration::from_std(delay).ok()?;
+        Self::new_at(Utc::now().checked_add_signed(delay)?)
+    }
+
+    /// 定时推送的时间
+    pub fn time(&self) -> &DateTime<FixedOffset> {
+        &self.0
+    }
+
+    /// 定时推送的毫秒时间戳
+    pub fn timestamp_millis(&self) -> i64 {
+        self.0.timestamp_millis()
+    }
+}
+
+impl NotifySerialize for Schedule {
+    fn serialize_field(&self) -> usize {
+        2
+    }
+
+    fn serialize<S: serde::Serializer>(
+        &self,
+        struct_serialize: &mut <S as serde::Serializer>::SerializeStruct,
+    ) -> Result<(), <S as serde::Serializer>::Error> {
+        struct_serialize.serialize_field("taskCron", &1)?;
+        struct_serialize.serialize_field("taskTime", &self.timestamp_millis())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use super::Schedule;
+
+    #[test]
+    fn test_new() {
+        assert!(Schedule::new_after(Duration::from_secs(60)).is_some());
+        // 推送时间溢出
+        assert!(Schedule::new_after(Duration::MAX).is_none());
+        assert!(Schedule::new_after(Duration::from_secs(u64::MAX / 1000)).is_none());
+        // 已过去的时间
+        assert!(Schedule::new_after(Duration::ZERO).is_none());
+        assert!(Schedule::new_at(Utc::now() - chrono::Duration::seconds(1)).is_none());
+    }
+}
@@ -0,0 +1,247 @@
+use serde::{ser::SerializeStruct, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::user_subscribe::UserMobId;
+
+/// 单次推送请求中 别名/标签/rid 列表的最大数量
+pub(crate) const MAX_TARGET_SIZE: usize = 1000;
+
+/// 推送目标
+#[derive(Debug, Clone)]
+pub enum PushTarget {
+    /// 广播推送，推送给应用的全部设备
+    All,
+    /// 按别名推送
+    Alias(Vec<String>),
+    /// 按标签推送
+    Tags {
+        tags: Vec<String>,
+        combine: TagsCombine,
+    },
+    /// 按地理位置推送
+    Area(Area),
+    /// 按 registration id 推送
+    Rids(Vec<String>),
+}
+
+/// 多个标签之间的组合方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagsCombine {
+    /// 并集，设备拥有任一标签即推送
+    #[default]
+    Or,
+    /// 交集，设备需要拥有全部标签才推送
+    And,
+}
+
+impl TagsCombine {
+    fn to_code(self) -> i32 {
+        match self {
+            TagsCombine::Or => 1,
+            TagsCombine::And => 2,
+        }
+    }
+}
+
+/// 地理位置推送目标
+///
+/// 未设置的层级不做限制
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option, into)))]
+pub struct Area {
+    /// 国家
+    pub(crate) country: Option<String>,
+    /// 省份
+    pub(crate) province: Option<String>,
+    /// 城市
+    pub(crate) city: Option<String>,
+}
+
+impl PushTarget {
+    /// 创建一个 **广播** 推送目标
+    pub fn new_all() -> Self {
+        Self::All
+    }
+
+    /// 创建一个 **别名** 推送目标
+    pub fn new_alias<I, T>(alias: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = T>,
+    {
+        Self::Alias(alias.into_iter().map(Into::into).collect())
+    }
+
+    /// 创建一个 **标签** 推送目标
+    pub fn new_tags<I, T>(tags: I, combine: TagsCombine) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = T>,
+    {
+        Self::Tags {
+            tags: tags.into_iter().map(Into::into).collect(),
+            combine,
+        }
+    }
+
+    /// 创建一个 **地理位置** 推送目标
+    pub fn new_area(area: Area) -> Self {
+        Self::Area(area)
+    }
+
+    /// 创建一个 **registration id** 推送目标
+    pub fn new_rids<I, T>(rids: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = T>,
+    {
+        Self::Rids(rids.into_iter().map(Into::into).collect())
+    }
+}
+
+impl PushTarget {
+    /// 从订阅用户中取出至多 `batch_size` 个用户, 构造 rid 推送目标
+    pub(crate) fn new_from_users(
+        user_iter: &mut impl Iterator<Item = impl UserMobId>,
+        batch_size: usize,
+    ) -> Option<Self> {
+        let vec = user_iter
+            .take(batch_size)
+            .map(|user| user.get_mob_id().to_string())
+            .collect::<Vec<_>>();
+
+        if vec.is_empty() {
+            None
+        } else {
+            Some(Self::Rids(vec))
+        }
+    }
+
+    /// 将别名与 rid 列表按照每批至多 `batch_size` 个拆分为多个推送目标
+    pub(crate) fn into_batches(self, batch_size: usize) -> Vec<Self> {
+        let chunks = |list: Vec<String>, f: fn(Vec<String>) -> PushTarget| -> Vec<PushTarget> {
+            list.chunks(batch_size)
+                .map(|chunk| f(chunk.to_vec()))
+                .collect()
+        };
+
+        match self {
+            PushTarget::Alias(alias) => chunks(alias, PushTarget::Alias),
+            PushTarget::Rids(rids) => chunks(rids, PushTarget::Rids),
+            target => vec![target],
+        }
+    }
+
+    /// 当前推送目标包含的 别名/标签/rid 数量, 广播与地理位置推送为 `None`
+    pub(crate) fn target_size(&self) -> Option<usize> {
+        match self {
+            PushTarget::All | PushTarget::Area(_) => None,
+            PushTarget::Alias(list)
+            | PushTarget::Tags { tags: list, .. }
+            | PushTarget::Rids(list) => Some(list.len()),
+        }
+    }
+
+    fn to_code(&self) -> i32 {
+        match self {
+            PushTarget::All => 1,
+            PushTarget::Alias(_) => 2,
+            PushTarget::Tags { .. } => 3,
+            PushTarget::Rids(_) => 4,
+            PushTarget::Area(_) => 5,
+        }
+    }
+}
+
+impl Serialize for PushTarget {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let len = match self {
+            PushTarget::All => 1,
+            PushTarget::Alias(_) | PushTarget::Rids(_) => 2,
+            PushTarget::Tags { .. } => 3,
+            PushTarget::Area(Area {
+                country,
+                province,
+                city,
+            }) => {
+                1 + [country, province, city]
+                    .into_iter()
+                    .filter(|field| field.is_some())
+                    .count()
+            }
+        };
+        let mut push_target = serializer.serialize_struct("pushTarget", len)?;
+
+        push_target.serialize_field("target", &self.to_code())?;
+        match self {
+            PushTarget::All => {}
+            PushTarget::Alias(alias) => push_target.serialize_field("alias", alias)?,
+            PushTarget::Tags { tags, combine } => {
+                push_target.serialize_field("tags", tags)?;
+                push_target.serialize_field("tagsType", &combine.to_code())?;
+            }
+            PushTarget::Area(Area {
+                country,
+                province,
+                city,
+            }) => {
+                if let Some(country) = country {
+                    push_target.serialize_field("country", country)?;
+                }
+                if let Some(province) = province {
+                    push_target.serialize_field("province", province)?;
+                }
+                if let Some(city) = city {
+                    push_target.serialize_field("city", city)?;
+                }
+            }
+            PushTarget::Rids(rids) => push_target.serialize_field("rids", rids)?,
+        }
+
+        push_target.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{Area, PushTarget, TagsCombine, MAX_TARGET_SIZE};
+
+    #[test]
+    fn test_serde() {
+        assert_eq!(
+            serde_json::to_value(PushTarget::new_all()).unwrap(),
+            json!({"target": 1})
+        );
+        assert_eq!(
+            serde_json::to_value(PushTarget::new_tags(["a", "b"], TagsCombine::And)).unwrap(),
+            json!({"target": 3, "tags": ["a", "b"], "tagsType": 2})
+        );
+        assert_eq!(
+            serde_json::to_value(PushTarget::new_area(
+                Area::builder().province("上海").build()
+            ))
+            .unwrap(),
+            json!({"target": 5, "province": "上海"})
+        );
+    }
+
+    #[test]
+    fn test_batches() {
+        let batches = PushTarget::new_rids((0..MAX_TARGET_SIZE * 2 + 1).map(|i| i.to_string()))
+            .into_batches(MAX_TARGET_SIZE);
+
+        assert_eq!(
+            batches
+                .iter()
+                .map(|target| target.target_size().unwrap())
+                .collect::<Vec<_>>(),
+            [MAX_TARGET_SIZE, MAX_TARGET_SIZE, 1]
+        );
+        assert_eq!(PushTarget::new_all().into_batches(10).len(), 1);
+    }
+}
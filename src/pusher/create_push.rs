@@ -1,121 +1,748 @@
-use std::{ops::Deref, time::Duration};
-use tokio::time::interval;
-use tracing::{error, info, instrument};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashSet, VecDeque},
+    ops::Deref,
+    time::SystemTime,
+};
+
+use chrono::{DateTime, FixedOffset, Utc};
+use futures_util::{stream, stream::FuturesUnordered, StreamExt};
+use tokio::time::{sleep, Instant};
+use tracing::{error, info, instrument, warn};
 
 use crate::{
-    config::get_config,
-    error::MobPushError,
-    http_client::{PushClient, PushRequestBuilder, PushResponse},
-    pusher::push_model::Forward,
-    PushEntity, UserSubscribeManage,
+    api::post_signed, error::MobPushError, http_client::PushClient, message_kind::NotifyType,
+    platform::split_by_platform, push_target::MAX_TARGET_SIZE, pusher::push_model::Forward,
+    Platforms, PushEntity, PushTarget, QuietHoursMode, Schedule, UserMobId, UserSubscribeManage,
 };
 
 use super::{
-    push_model::{CreatePush, PushNotify, PushTarget, Respond},
-    MobPusher,
+    outbox::{BoxError, OutboxEntry, OutboxId, PlannedBatch, PlannedDelivery},
+    push_model::{CreatePush, PushNotify, ResBody},
+    rate_limit::{RateLimit, RateLimiter},
+    shutdown::{InterruptedPush, ShutdownSummary, UnsentBatch},
+    BatchResult, MobPusher, PushResult, PushWorker,
 };
 
-impl<M: UserSubscribeManage, C: PushClient> MobPusher<M, C> {
+impl<M: UserSubscribeManage, C: PushClient> PushWorker<M, C> {
+    /// 发送单个批次的推送请求, 可重试的异常将按照重试策略重试
+    async fn request_with_retry(
+        &self,
+        body: &CreatePush<'_>,
+    ) -> Result<Option<ResBody>, (MobPushError<M, C>, u32)> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            self.rate_limiter.acquire().await;
+            match post_signed(&self.client, self.config.get(), "/v3/push/createPush", body).await {
+                Ok(resp) => break Ok(resp),
+                Err(err) => {
+                    let err = MobPushError::from(err);
+                    if !err.is_retryable() || attempt >= self.retry_policy.max_attempts {
+                        break Err((err, attempt));
+                    }
+                    let delay = self.retry_policy.delay(attempt, err.is_rate_limited());
+                    warn!(
+                        event = "Retry Pushing",
+                        push.attempt = attempt,
+                        push.rate_limited = err.is_rate_limited(),
+                        push.delay = ?delay
+                    );
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// 推送单个批次
+    async fn push_batch(
+        &self,
+        data: &M::PushData,
+        push_target: PushTarget,
+        notify_type: NotifyType<'_>,
+        platforms: &Platforms,
+        delivery: &PlannedDelivery,
+        workno: Option<String>,
+    ) -> Result<BatchResult, MobPushError<M, C>> {
+        let batch_size = push_target.target_size();
+        let config = self.config.get();
+        let mut push_notify = PushNotify::new_with_builder(data, notify_type, platforms.clone());
+        push_notify.set_ios_production(config.ios_production);
+        match delivery {
+            PlannedDelivery::Normal => {}
+            PlannedDelivery::Deferred(schedule) => {
+                push_notify.set_schedule(schedule.clone());
+            }
+            PlannedDelivery::Silent => {
+                push_notify.set_silent();
+            }
+        }
+        // request body
+        let body = CreatePush {
+            app_key: &config.key,
+            push_target,
+            push_notify,
+            push_forward: Forward::new(data),
+            workno,
+        };
+
+        info!(
+            event = "Prepare to Push",
+            users.batch_size = batch_size,
+            push.kind = notify_type.to_code()
+        );
+        let sent_at = SystemTime::now();
+        let start = Instant::now();
+        match self.request_with_retry(&body).await {
+            Ok(resp) => {
+                let batch = BatchResult {
+                    batch_id: resp
+                        .map(|res| res.batch_id)
+                        .filter(|batch_id| !batch_id.is_empty()),
+                    recipients: batch_size,
+                    sent_at,
+                    elapsed: start.elapsed(),
+                };
+                info!(
+                    event = "Push Batch Created",
+                    push.batch_id = batch.batch_id.as_deref(),
+                    push.elapsed = ?batch.elapsed
+                );
+                Ok(batch)
+            }
+            Err((err, attempts)) => {
+                warn!(
+                    event = "Push Batch Failed",
+                    users.batch_size = batch_size,
+                    push.attempts = attempts
+                );
+                Err(MobPushError::Batch {
+                    target: body.push_target,
+                    attempts,
+                    error: Box::new(err),
+                })
+            }
+        }
+    }
+
+    /// 推送一组批次, 同时至多发送 `batch_concurrency` 个批次,
+    /// 单个批次失败不影响其余批次, 已被 Mob 接受的批次将被跳过
+    ///
+    /// `offset` 为第一个批次在消息全部批次中的序号
+    ///
+    /// 关闭期限到达后不再发送新的批次, 进行中的请求将被取消
     #[instrument(skip_all, name = "processPushing")]
     async fn pushing(
-        client: &C,
-        data: M::PushData,
-        mut users: impl Iterator<Item = M::UserIdentify>,
-    ) -> Result<(), MobPushError<M, C>> {
-        let mut timer = interval(Duration::from_millis(500));
-        while let Some(push_target) = PushTarget::new(&mut users) {
-            let batch_size = push_target.target_user.len();
-            // request body
-            let body = CreatePush {
-                push_target,
-                push_notify: PushNotify::new_with_builder(&data),
-                push_forward: Forward::new(&data),
+        &self,
+        data: &M::PushData,
+        batches: &[PlannedBatch],
+        offset: usize,
+        progress: Option<&Progress>,
+        outcome: &mut PushOutcome<M, C>,
+    ) {
+        let message_kind = data.message_kind();
+        let identity = data.identity();
+        // 推送请求在被轮询前不会发送
+        let mut requests = Vec::with_capacity(batches.len());
+        for (index, batch) in (offset..).zip(batches) {
+            if progress.is_some_and(|progress| progress.acked.contains(&index)) {
+                continue;
+            }
+            let Some(notify_type) = message_kind.notify_type(batch.passthrough) else {
+                warn!(
+                    event = "Skip Batch of Unknown Kind",
+                    push.passthrough = batch.passthrough
+                );
+                continue;
+            };
+            let unsent = UnsentBatch {
+                target: batch.target.clone(),
+                passthrough: batch.passthrough,
             };
+            let workno = identity
+                .as_deref()
+                .map(|identity| new_workno(identity, index));
+            let request = self.push_batch(
+                data,
+                batch.target.clone(),
+                notify_type,
+                &batch.platforms,
+                &batch.delivery,
+                workno,
+            );
+            requests.push(async move {
+                tokio::select! {
+                    biased;
+                    _ = self.shutdown.deadline() => (index, Err(unsent)),
+                    resp = request => (index, Ok(resp)),
+                }
+            });
+        }
+
+        let mut responses = stream::iter(requests).buffer_unordered(self.batch_concurrency);
+        while let Some((index, resp)) = responses.next().await {
+            match resp {
+                Ok(Ok(batch)) => {
+                    if let (Some(outbox), Some(progress)) = (&self.outbox, progress) {
+                        if let Err(err) = outbox.ack(progress.id, index).await {
+                            outcome.failures.push(MobPushError::Outbox(err));
+                        }
+                    }
+                    outcome.batches.push(batch)
+                }
+                Ok(Err(err)) => outcome.failures.push(err),
+                Err(unsent) => outcome.unsent.push(unsent),
+            }
+        }
+    }
 
-            let serde_body = serde_json::to_vec(&body)?;
+    /// 将新规划的批次记录到持久化队列后推送
+    async fn push_planned(
+        &self,
+        data: &M::PushData,
+        batches: Vec<PlannedBatch>,
+        progress: Option<&Progress>,
+        outcome: &mut PushOutcome<M, C>,
+    ) {
+        if batches.is_empty() {
+            return;
+        }
+        if let (Some(outbox), Some(progress)) = (&self.outbox, progress) {
+            if let Err(err) = outbox.plan(progress.id, &batches).await {
+                outcome.failures.push(MobPushError::Outbox(err));
+            }
+        }
+        let offset = outcome.planned;
+        outcome.planned += batches.len();
+        self.pushing(data, &batches, offset, progress, outcome)
+            .await;
+    }
 
-            let md5_vec = {
-                let mut temp = serde_body.clone();
-                temp.extend(get_config().secret.as_bytes());
-                temp
+    /// 获取消息的推送目标并规划推送批次, 每凑满一批用户即推送
+    ///
+    /// `planned_rids` 中的用户已在恢复的批次中规划, 将被跳过。
+    /// 关闭期限到达时停止获取订阅用户, 返回 `Ok(false)`
+    async fn plan_and_push(
+        &self,
+        data: &M::PushData,
+        planned_rids: &HashSet<String>,
+        progress: Option<&Progress>,
+        outcome: &mut PushOutcome<M, C>,
+    ) -> Result<bool, MobPushError<M, C>> {
+        let platforms = data.platforms();
+        let batch_size = self.config.get().batch_size.clamp(1, MAX_TARGET_SIZE);
+        if let Some(target) = data.push_target() {
+            info!(event = "push to target", push.target = ?target);
+            let targets = target.into_batches(batch_size);
+            let batches = plan_batches(data, vec![(platforms, PlannedDelivery::Normal, targets)]);
+            self.push_planned(data, batches, progress, outcome).await;
+            return Ok(true);
+        }
+
+        let mut pages = self.manage.fetch_subscriber_pages(data.get_resource());
+        // 按照推送平台与送达方式分组, 尚未凑满一批的用户
+        let mut groups: Vec<(Platforms, PlannedDelivery, Vec<M::UserIdentify>)> = Vec::new();
+        let mut fetched = 0;
+        loop {
+            let page = tokio::select! {
+                biased;
+                _ = self.shutdown.deadline() => None,
+                page = pages.next() => Some(page),
             };
-            let md5_len = md5_vec.len();
-            let md5 = md5::compute(md5_vec);
-
-            info!(
-                event = "Prepare to Push",
-                users.batch_size = batch_size,
-                push.payload.len = serde_body.len(),
-                push.md5.len = md5_len,
-                push.md5.value = format!("{md5:x}")
-            );
-            // request
-            let req = client
-                .post(url::Url::parse("http://api.push.mob.com/v3/push/createPush").unwrap())
-                .default_headers()
-                .header("sign", &format!("{md5:x}"))
-                .body(serde_body)
-                .build()
-                .map_err(MobPushError::Request)?;
-
-            let resp = client
-                .send_request(req)
-                .await
-                .map_err(MobPushError::Request)?;
+            let Some(page) = page else {
+                warn!(event = "Shutdown while Fetching Subscribers");
+                // 已获取但尚未推送的用户视为未发送
+                for (platforms, delivery, users) in groups {
+                    let mut users = users.into_iter();
+                    let targets =
+                        std::iter::from_fn(|| PushTarget::new_from_users(&mut users, batch_size))
+                            .collect();
+                    for batch in plan_batches(data, vec![(platforms, delivery, targets)]) {
+                        outcome.unsent.push(UnsentBatch {
+                            target: batch.target,
+                            passthrough: batch.passthrough,
+                        });
+                    }
+                }
+                return Ok(false);
+            };
+            let Some(page) = page else { break };
+            let page = page.map_err(MobPushError::Manage)?;
+            fetched += page.len();
+            let page = self.check_subscribers(data, page, outcome).await;
+            // 消息定时推送时按照推送时间判断免打扰时段
+            let deliver_at = data
+                .schedule()
+                .map(|schedule| *schedule.time())
+                .unwrap_or_else(|| Utc::now().fixed_offset());
+
+            let mut touched = Vec::new();
+            for (platforms, users) in split_by_platform(page, &platforms) {
+                for user in users {
+                    if !planned_rids.is_empty()
+                        && planned_rids.contains(&user.get_mob_id().to_string())
+                    {
+                        continue;
+                    }
+                    let delivery = self.delivery_of(&user, &deliver_at);
+                    let idx = match groups
+                        .iter()
+                        .position(|(p, d, _)| *p == platforms && *d == delivery)
+                    {
+                        Some(idx) => idx,
+                        None => {
+                            groups.push((platforms.clone(), delivery, Vec::new()));
+                            groups.len() - 1
+                        }
+                    };
+                    groups[idx].2.push(user);
+                    if !touched.contains(&idx) {
+                        touched.push(idx);
+                    }
+                }
+            }
+            let mut targets = Vec::new();
+            for idx in touched {
+                let (platforms, delivery, group) = &mut groups[idx];
+                let mut full = Vec::new();
+                while group.len() >= batch_size {
+                    let mut users = group.drain(..batch_size);
+                    full.extend(PushTarget::new_from_users(&mut users, batch_size));
+                }
+                if !full.is_empty() {
+                    targets.push((platforms.clone(), delivery.clone(), full));
+                }
+            }
+            self.push_planned(data, plan_batches(data, targets), progress, outcome)
+                .await;
+        }
+
+        info!(event = "finger out subscribers", subscribers.len = fetched);
+        let targets = groups
+            .into_iter()
+            .filter(|(_, _, users)| !users.is_empty())
+            .map(|(platforms, delivery, users)| {
+                let mut users = users.into_iter();
+                let targets =
+                    std::iter::from_fn(|| PushTarget::new_from_users(&mut users, batch_size));
+                (platforms, delivery, targets.collect())
+            })
+            .collect();
+        self.push_planned(data, plan_batches(data, targets), progress, outcome)
+            .await;
+        Ok(true)
+    }
+
+    /// 按照免打扰时段确定用户在 `deliver_at` 时的送达方式
+    fn delivery_of(
+        &self,
+        user: &M::UserIdentify,
+        deliver_at: &DateTime<FixedOffset>,
+    ) -> PlannedDelivery {
+        let Some(mode) = self.quiet_hours else {
+            return PlannedDelivery::Normal;
+        };
+        let Some(end) = user
+            .quiet_hours()
+            .and_then(|quiet| quiet.end_after(deliver_at))
+        else {
+            return PlannedDelivery::Normal;
+        };
+        match mode {
+            QuietHoursMode::Defer => Schedule::new_at(end)
+                .map(PlannedDelivery::Deferred)
+                .unwrap_or(PlannedDelivery::Silent),
+            QuietHoursMode::Silent => PlannedDelivery::Silent,
+        }
+    }
+
+    /// 逐个用户检查订阅, 未订阅或检查失败的用户将被跳过
+    async fn check_subscribers(
+        &self,
+        data: &M::PushData,
+        users: Vec<M::UserIdentify>,
+        outcome: &mut PushOutcome<M, C>,
+    ) -> Vec<M::UserIdentify> {
+        let Some(checker) = &self.subscribe_check else {
+            return users;
+        };
+        let resource = data.get_resource();
+        let mut checks = Vec::with_capacity(users.len());
+        for user in users {
+            checks.push(async move {
+                let subscribed = checker.is_subscribed(&self.manage, &user, resource).await;
+                (user, subscribed)
+            });
+        }
+
+        let total = checks.len();
+        let mut subscribers = Vec::with_capacity(total);
+        let mut checks = stream::iter(checks).buffered(checker.concurrency());
+        while let Some((user, subscribed)) = checks.next().await {
+            match subscribed {
+                Ok(true) => subscribers.push(user),
+                Ok(false) => {}
+                Err(err) => outcome.failures.push(MobPushError::Manage(err)),
+            }
+        }
+        info!(
+            event = "Subscribers Checked",
+            subscribers.total = total,
+            subscribers.subscribed = subscribers.len()
+        );
+        subscribers
+    }
+
+    /// 持久化队列读写异常发送到异常接收端, 不影响推送
+    async fn report_outbox<R>(&self, result: Result<R, BoxError>) -> Option<R>
+    where
+        C::Error: std::error::Error,
+    {
+        match result {
+            Ok(resp) => Some(resp),
+            Err(err) => {
+                let err = MobPushError::Outbox(err);
+                error!(event = "Outbox Error", error = %err);
+                self.error_sink.send(err).await;
+                None
+            }
+        }
+    }
+
+    /// 消息推送完成, 从持久化队列中移除
+    async fn complete(&self, progress: Option<Progress>)
+    where
+        C::Error: std::error::Error,
+    {
+        if let (Some(outbox), Some(progress)) = (&self.outbox, progress) {
+            self.report_outbox(outbox.complete(progress.id).await).await;
+        }
+    }
 
-            // handle respond
-            let resp = resp.bytes().await.map_err(MobPushError::Request)?;
+    /// 推送一条消息, 窗口期内已推送的消息将被跳过
+    ///
+    /// 全部批次均推送失败的消息不占用去重窗口, 可再次推送
+    async fn process(&self, job: Job<M::PushData>) -> Processed<M::PushData>
+    where
+        C::Error: std::error::Error,
+    {
+        let Some(dedup) = &self.dedup else {
+            return self.push_job(job).await;
+        };
+        let Some(identity) = job.data().identity().map(Cow::into_owned) else {
+            return self.push_job(job).await;
+        };
+        // 恢复的消息此前已通过去重检查
+        if !dedup.begin(&identity) && matches!(job, Job::New(_)) {
+            info!(event = "Skip Duplicate Pushing", data.identity = identity);
+            return Processed::Done;
+        }
+        let processed = self.push_job(job).await;
+        dedup.finish(&identity, matches!(processed, Processed::Done));
+        processed
+    }
 
-            let resp: Respond = serde_json::from_slice(&resp)?;
+    /// 推送一条消息, 推送结果与异常分别发送到对应接收端
+    async fn push_job(&self, job: Job<M::PushData>) -> Processed<M::PushData>
+    where
+        C::Error: std::error::Error,
+    {
+        let (data, progress, recovered, planned) = match job {
+            Job::New(data) => {
+                let progress = match &self.outbox {
+                    Some(outbox) => {
+                        self.report_outbox(outbox.accept(&data).await)
+                            .await
+                            .map(|id| Progress {
+                                id,
+                                acked: BTreeSet::new(),
+                            })
+                    }
+                    None => None,
+                };
+                (data, progress, Vec::new(), false)
+            }
+            Job::Recovered(OutboxEntry {
+                id,
+                data,
+                plan,
+                planned,
+                acked,
+            }) => (data, Some(Progress { id, acked }), plan, planned),
+        };
+        info!(
+            event = "PushData income",
+            data.title = data.get_title().deref()
+        );
+        let started_at = SystemTime::now();
+        let start = Instant::now();
 
-            println!("{resp:?}");
+        let mut outcome = PushOutcome {
+            batches: Vec::new(),
+            failures: Vec::new(),
+            unsent: Vec::new(),
+            planned: 0,
+        };
+        // 恢复的批次中已规划的用户
+        let planned_rids = match planned {
+            true => HashSet::new(),
+            false => recovered
+                .iter()
+                .filter_map(|batch| match &batch.target {
+                    PushTarget::Rids(rids) => Some(rids),
+                    _ => None,
+                })
+                .flatten()
+                .cloned()
+                .collect(),
+        };
+        if !recovered.is_empty() {
+            outcome.planned = recovered.len();
+            self.pushing(&data, &recovered, 0, progress.as_ref(), &mut outcome)
+                .await;
+        }
+        drop(recovered);
 
-            match resp.status {
-                200 => {}
-                state => {
-                    let msg = resp.error.unwrap();
-                    Err(MobPushError::Mob { state, msg })?;
+        let mut fetched_all = true;
+        if !planned {
+            match self
+                .plan_and_push(&data, &planned_rids, progress.as_ref(), &mut outcome)
+                .await
+            {
+                Ok(true) => {
+                    if let (Some(outbox), Some(progress)) = (&self.outbox, &progress) {
+                        if let Err(err) = outbox.planned(progress.id).await {
+                            outcome.failures.push(MobPushError::Outbox(err));
+                        }
+                    }
                 }
+                Ok(false) => fetched_all = false,
+                Err(err) => outcome.failures.push(err),
+            }
+        }
+
+        let PushOutcome {
+            batches,
+            failures,
+            unsent,
+            planned,
+        } = outcome;
+        for err in failures {
+            error!(event="Error while Pushing Batch",error = %err);
+            self.error_sink.send(err).await;
+        }
+        if !unsent.is_empty() || !fetched_all {
+            warn!(
+                event = "Pushing Interrupted by Shutdown",
+                push.unsent = unsent.len()
+            );
+            if progress.is_some() {
+                return Processed::Persisted;
+            }
+            // 尚未规划任何批次, 可完整地重新推送
+            if planned == 0 && unsent.is_empty() {
+                return Processed::Pending(data);
+            }
+            return Processed::Interrupted(InterruptedPush {
+                data,
+                batches,
+                unsent,
+                unfetched: !fetched_all,
+            });
+        }
+        let acked = progress
+            .as_ref()
+            .is_some_and(|progress| !progress.acked.is_empty());
+        self.complete(progress).await;
+        // 全部批次失败时不发送推送结果
+        if batches.is_empty() && planned > 0 && !acked {
+            return Processed::Failed;
+        }
+        if !batches.is_empty() {
+            let result = PushResult {
+                data,
+                batches,
+                started_at,
+                elapsed: start.elapsed(),
             };
+            if let Some(result_sender) = &self.result_send {
+                // 推送结果接收端可能已被丢弃
+                result_sender.send(result).await.ok();
+            }
+        }
+        Processed::Done
+    }
+}
 
-            // delay
-            timer.tick().await;
+/// 将推送目标展开为推送批次, 每个推送目标按照消息类型创建一至两个批次
+///
+/// 推送平台为空的推送目标将被跳过
+fn plan_batches<T: PushEntity>(
+    data: &T,
+    targets: Vec<(Platforms, PlannedDelivery, Vec<PushTarget>)>,
+) -> Vec<PlannedBatch> {
+    let message_kind = data.message_kind();
+    let notify_types = message_kind.notify_types();
+    let mut plan = Vec::new();
+    for (platforms, delivery, targets) in targets {
+        if platforms.is_empty() {
+            warn!(event = "Empty Push Platforms, Batch Skipped");
+            continue;
+        }
+        for target in targets {
+            for notify_type in &notify_types {
+                plan.push(PlannedBatch {
+                    target: target.clone(),
+                    platforms: platforms.clone(),
+                    passthrough: notify_type.is_passthrough(),
+                    delivery: delivery.clone(),
+                });
+            }
         }
+    }
+    plan
+}
+
+/// 由消息标识与批次序号生成推送批次的 workno
+fn new_workno(identity: &str, index: usize) -> String {
+    format!("{:x}-{index}", md5::compute(identity))
+}
+
+/// 持久化队列中一条消息的推送进度
+struct Progress {
+    id: OutboxId,
+    acked: BTreeSet<usize>,
+}
+
+/// 待推送的消息
+enum Job<T> {
+    /// 新接收的消息
+    New(T),
+    /// 从持久化队列中恢复的消息
+    Recovered(OutboxEntry<T>),
+}
 
-        Ok(())
+impl<T> Job<T> {
+    fn data(&self) -> &T {
+        match self {
+            Job::New(data) => data,
+            Job::Recovered(entry) => &entry.data,
+        }
     }
+}
+
+/// 一条消息全部批次的推送情况
+struct PushOutcome<M: UserSubscribeManage, C: PushClient> {
+    batches: Vec<BatchResult>,
+    failures: Vec<MobPushError<M, C>>,
+    unsent: Vec<UnsentBatch>,
+    /// 已规划的批次数量, 即下一个批次的序号
+    planned: usize,
+}
+
+/// 一条消息的处理结果
+enum Processed<T> {
+    /// 推送完成, 至少一个批次被 Mob 接受, 或无需推送
+    Done,
+    /// 推送完成, 但全部批次均推送失败
+    Failed,
+    /// 关闭时尚未开始推送
+    Pending(T),
+    /// 关闭时推送被中断
+    Interrupted(InterruptedPush<T>),
+    /// 关闭时推送未完成, 进度已记录到持久化队列, 下次启动时恢复
+    Persisted,
+}
 
+impl<M: UserSubscribeManage, C: PushClient> MobPusher<M, C> {
+    /// 启动推送器
+    ///
+    /// 设置了持久化队列时, 先恢复上次未完成的消息。
+    /// 全部消息发送端被丢弃, 或者通过 [`ShutdownHandle`](super::ShutdownHandle) 关闭后返回,
+    /// 返回值中包含关闭时未完成推送的消息
     #[instrument(name = "PushTask", skip_all)]
-    pub async fn start_up(mut self)
+    pub async fn start_up(self) -> ShutdownSummary<M::PushData>
     where
         C::Error: std::error::Error,
     {
-        let mut timer = interval(Duration::from_millis(500));
-        while let Some(data) = self.income_channel.recv().await {
-            info!(
-                event = "PushData income",
-                data.title = data.get_title().deref()
-            );
-            let error_sender = self.error_send.clone();
-            let task = async {
-                let subscribers = self.manage.fetch_all_subscriber(data.get_resource());
-                let subscribers = subscribers.await.map_err(MobPushError::Manage)?;
+        let Self {
+            mut income_channel,
+            entity_concurrency,
+            rate_limit,
+            mut worker,
+        } = self;
+        let rate_limit = rate_limit.unwrap_or(worker.config.get().rate_limit);
+        // 配置的字段可在校验后被修改, 无效时使用默认的速率限制, 避免推送器停滞
+        let rate_limit = match rate_limit.validate() {
+            Ok(()) => rate_limit,
+            Err(err) => {
+                error!(event = "Invalid Rate Limit", error = %err, ?rate_limit);
+                RateLimit::default()
+            }
+        };
+        worker.rate_limiter = RateLimiter::new(rate_limit);
 
+        let mut recovered = VecDeque::new();
+        if let Some(outbox) = &worker.outbox {
+            if let Some(entries) = worker.report_outbox(outbox.recover().await).await {
                 info!(
-                    event = "finger out subscribers",
-                    subscribers.len = subscribers.len()
+                    event = "Recover Pushing from Outbox",
+                    push.recovered = entries.len()
                 );
-                Self::pushing(&self.client, data, subscribers.into_iter()).await?;
-                Result::<_, MobPushError<_, _>>::Ok(())
-            };
-            match task.await {
-                Ok(_) => {}
-                Err(err) => {
-                    error!(event="Error while Pushing",error = %err);
-                    error_sender.send(err).await.expect("Receive half closed")
+                recovered.extend(entries);
+            }
+        }
+
+        let mut summary = ShutdownSummary::default();
+        let mut processing = FuturesUnordered::new();
+        // 触发关闭或全部发送端被丢弃后不再接收新的消息
+        let mut accepting = true;
+        loop {
+            // 优先推送恢复的消息
+            while accepting && processing.len() < entity_concurrency {
+                match recovered.pop_front() {
+                    Some(entry) => processing.push(worker.process(Job::Recovered(entry))),
+                    None => break,
+                }
+            }
+            tokio::select! {
+                biased;
+                Some(processed) = processing.next() => match processed {
+                    Processed::Done | Processed::Failed | Processed::Persisted => {}
+                    Processed::Pending(data) => summary.pending.push(data),
+                    Processed::Interrupted(interrupted) => summary.interrupted.push(interrupted),
+                },
+                _ = worker.shutdown.triggered(), if accepting => accepting = false,
+                data = income_channel.recv(), if accepting && recovered.is_empty() && processing.len() < entity_concurrency => {
+                    match data {
+                        Some(data) => processing.push(worker.process(Job::New(data))),
+                        None => accepting = false,
+                    }
+                }
+                else => break,
+            }
+        }
+        // 关闭时仍在通道中的消息, 优先记录到持久化队列
+        income_channel.close();
+        while let Ok(data) = income_channel.try_recv() {
+            if let Some(outbox) = &worker.outbox {
+                if worker
+                    .report_outbox(outbox.accept(&data).await)
+                    .await
+                    .is_some()
+                {
+                    continue;
                 }
             }
-            timer.tick().await;
+            summary.pending.push(data);
+        }
+        if !summary.is_empty() {
+            warn!(
+                event = "Shutdown with Unsent Pushing",
+                push.pending = summary.pending.len(),
+                push.interrupted = summary.interrupted.len()
+            );
         }
+        summary
     }
 }
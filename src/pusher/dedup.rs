@@ -0,0 +1,88 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+/// 本地去重窗口, 拒绝重复推送窗口期内已完成推送的消息
+#[derive(Debug)]
+pub(crate) struct DedupWindow {
+    window: Duration,
+    seen: Mutex<HashMap<String, Seen>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Seen {
+    /// 正在推送
+    InFlight,
+    /// 推送完成的时间
+    Completed(Instant),
+}
+
+impl DedupWindow {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Seen>> {
+        self.seen.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 开始推送指定标识的消息, 该标识正在推送或在窗口期内已完成推送时返回 `false`
+    pub(crate) fn begin(&self, identity: &str) -> bool {
+        let now = Instant::now();
+        let mut seen = self.lock();
+        seen.retain(|_, state| match state {
+            Seen::InFlight => true,
+            Seen::Completed(at) => now.duration_since(*at) < self.window,
+        });
+        match seen.entry(identity.to_owned()) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(Seen::InFlight);
+                true
+            }
+        }
+    }
+
+    /// 结束推送指定标识的消息
+    ///
+    /// 推送完成时在窗口期内拒绝相同标识的消息, 否则允许再次推送
+    pub(crate) fn finish(&self, identity: &str, completed: bool) {
+        let mut seen = self.lock();
+        if completed {
+            seen.insert(identity.to_owned(), Seen::Completed(Instant::now()));
+        } else {
+            seen.remove(identity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::DedupWindow;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dedup() {
+        let dedup = DedupWindow::new(Duration::from_secs(60));
+
+        assert!(dedup.begin("a"));
+        assert!(!dedup.begin("a"));
+        // 未完成的推送不占用窗口
+        dedup.finish("a", false);
+        assert!(dedup.begin("a"));
+        dedup.finish("a", true);
+        assert!(!dedup.begin("a"));
+        assert!(dedup.begin("b"));
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert!(dedup.begin("a"));
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// 推送异常的去向
+///
+/// 推送异常总会通过 `tracing` 记录, 无论是否有接收方,
+/// 推送器都将继续运行
+pub enum ErrorSink<E> {
+    /// 发送到 channel, channel 已满时等待接收方读取
+    ///
+    /// 接收端被丢弃后, 异常仅通过 `tracing` 记录
+    Channel(mpsc::Sender<E>),
+    /// 调用回调函数
+    Callback(Box<dyn Fn(E) + Send + Sync>),
+    /// 仅通过 `tracing` 记录
+    Tracing,
+    /// 保存到有界环形缓冲区, 已满时丢弃最早的异常
+    Ring(ErrorRing<E>),
+}
+
+impl<E> ErrorSink<E> {
+    /// 发送到 channel, 返回异常接收端
+    pub fn new_channel(buff_size: usize) -> (Self, mpsc::Receiver<E>) {
+        let (tx, rx) = mpsc::channel(buff_size);
+        (Self::Channel(tx), rx)
+    }
+
+    /// 每个异常调用一次回调函数
+    ///
+    /// 回调函数在推送任务中同步执行, 不应阻塞
+    pub fn new_callback(callback: impl Fn(E) + Send + Sync + 'static) -> Self {
+        Self::Callback(Box::new(callback))
+    }
+
+    /// 仅通过 `tracing` 记录
+    pub fn new_tracing() -> Self {
+        Self::Tracing
+    }
+
+    /// 保存到容量为 `capacity` 的环形缓冲区, 返回缓冲区的读取句柄
+    pub fn new_ring(capacity: usize) -> (Self, ErrorRing<E>) {
+        let ring = ErrorRing::new(capacity);
+        (Self::Ring(ring.clone()), ring)
+    }
+
+    pub(crate) async fn send(&self, err: E) {
+        match self {
+            ErrorSink::Channel(sender) => {
+                if sender.send(err).await.is_err() {
+                    debug!(event = "Error Receiver Dropped");
+                }
+            }
+            ErrorSink::Callback(callback) => callback(err),
+            ErrorSink::Tracing => {}
+            ErrorSink::Ring(ring) => ring.push(err),
+        }
+    }
+}
+
+impl<E> Debug for ErrorSink<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Channel(_) => f.write_str("Channel"),
+            Self::Callback(_) => f.write_str("Callback"),
+            Self::Tracing => f.write_str("Tracing"),
+            Self::Ring(ring) => f.debug_tuple("Ring").field(&ring.capacity).finish(),
+        }
+    }
+}
+
+/// 保存推送异常的有界环形缓冲区
+///
+/// 可在多处克隆持有, 共享同一个缓冲区
+pub struct ErrorRing<E> {
+    capacity: usize,
+    inner: Arc<Mutex<RingInner<E>>>,
+}
+
+struct RingInner<E> {
+    errors: VecDeque<E>,
+    dropped: u64,
+}
+
+impl<E> Clone for ErrorRing<E> {
+    fn clone(&self) -> Self {
+        Self {
+            capacity: self.capacity,
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<E> ErrorRing<E> {
+    /// 容量为 0 时视为 1
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            inner: Arc::new(Mutex::new(RingInner {
+                errors: VecDeque::with_capacity(capacity),
+                dropped: 0,
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RingInner<E>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn push(&self, err: E) {
+        let mut inner = self.lock();
+        if inner.errors.len() >= self.capacity {
+            inner.errors.pop_front();
+            inner.dropped += 1;
+        }
+        inner.errors.push_back(err);
+    }
+
+    /// 取出缓冲区中的全部异常, 由旧到新
+    pub fn drain(&self) -> Vec<E> {
+        self.lock().errors.drain(..).collect()
+    }
+
+    /// 缓冲区中的异常数量
+    pub fn len(&self) -> usize {
+        self.lock().errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().errors.is_empty()
+    }
+
+    /// 因缓冲区已满而被丢弃的异常总数
+    pub fn dropped(&self) -> u64 {
+        self.lock().dropped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ErrorSink;
+
+    #[tokio::test]
+    async fn test_sinks() {
+        let (sink, ring) = ErrorSink::new_ring(2);
+        for err in 0..5 {
+            sink.send(err).await;
+        }
+        assert_eq!(ring.dropped(), 3);
+        assert_eq!(ring.drain(), [3, 4]);
+        assert!(ring.is_empty());
+
+        // 接收方读取时不丢弃异常
+        let (sink, mut rx) = ErrorSink::new_channel(1);
+        let sending = async move {
+            for err in 0..5 {
+                sink.send(err).await;
+            }
+        };
+        let receiving = async {
+            let mut errors = Vec::new();
+            while let Some(err) = rx.recv().await {
+                errors.push(err);
+            }
+            errors
+        };
+        let ((), errors) = tokio::join!(sending, receiving);
+        assert_eq!(errors, [0, 1, 2, 3, 4]);
+
+        // 接收端被丢弃后不再等待
+        let (sink, rx) = ErrorSink::new_channel(1);
+        drop(rx);
+        sink.send(0).await;
+        sink.send(1).await;
+    }
+}
@@ -1,43 +1,213 @@
 mod create_push;
-mod push_model;
+mod dedup;
+mod error_sink;
+mod outbox;
+pub(crate) mod push_model;
+mod push_result;
+mod rate_limit;
+mod retry;
+mod shutdown;
+mod subscribe_check;
+
+use std::{sync::Arc, time::Duration};
 
 use tokio::sync::mpsc;
 
 use crate::{
-    error::{self, MobPushError},
+    config::{ConfigError, ConfigRef},
+    error::MobPushError,
     http_client::PushClient,
-    UserSubscribeManage,
+    MobPushConfig, QuietHoursMode, UserSubscribeManage,
+};
+
+use self::{
+    dedup::DedupWindow, outbox::DynOutbox, rate_limit::RateLimiter, shutdown::ShutdownSignal,
+    subscribe_check::SubscribeChecker,
+};
+pub use self::{
+    error_sink::{ErrorRing, ErrorSink},
+    outbox::{FileOutbox, Outbox, OutboxEntry, OutboxId, PlannedBatch, PlannedDelivery},
+    push_result::{BatchResult, PushResult},
+    rate_limit::RateLimit,
+    retry::RetryPolicy,
+    shutdown::{InterruptedPush, ShutdownHandle, ShutdownSummary, UnsentBatch},
+    subscribe_check::{SubscribeCheck, SubscribeCheckMode},
 };
 
 /// mob push 推送器
 pub struct MobPusher<M: UserSubscribeManage, C: PushClient> {
+    income_channel: mpsc::Receiver<M::PushData>,
+    /// 同时推送的消息数量
+    entity_concurrency: usize,
+    /// 推送请求的速率限制, 未设置时使用配置中的速率限制
+    rate_limit: Option<RateLimit>,
+    worker: PushWorker<M, C>,
+}
+
+/// 推送器中被全部推送任务共享的部分
+struct PushWorker<M: UserSubscribeManage, C: PushClient> {
     manage: M,
     client: C,
-    income_channel: mpsc::Receiver<M::PushData>,
-    error_send: mpsc::Sender<error::MobPushError<M, C>>,
+    config: ConfigRef,
+    error_sink: ErrorSink<MobPushError<M, C>>,
+    result_send: Option<mpsc::Sender<PushResult<M::PushData>>>,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    /// 单条消息同时发送的推送批次数量
+    batch_concurrency: usize,
+    shutdown: ShutdownSignal,
+    outbox: Option<Box<dyn DynOutbox<M::PushData>>>,
+    dedup: Option<DedupWindow>,
+    subscribe_check: Option<SubscribeChecker<M>>,
+    quiet_hours: Option<QuietHoursMode>,
 }
 
 impl<M: UserSubscribeManage, C: PushClient> MobPusher<M, C> {
+    /// 创建推送器, 推送异常发送到返回的异常接收端
+    ///
+    /// 可通过 [`global_config`](crate::global_config) 使用全局配置
+    ///
+    /// 异常接收端需要及时读取, 否则推送器将等待接收端读取;
+    /// 异常接收端被丢弃后, 推送异常仅通过 `tracing` 记录
     pub fn new(
         client: C,
         manage: M,
+        config: impl Into<Arc<MobPushConfig>>,
         buff_size: usize,
     ) -> (
         Self,
         mpsc::Sender<M::PushData>,
         mpsc::Receiver<MobPushError<M, C>>,
     ) {
+        let (error_sink, err_rx) = ErrorSink::new_channel(16);
+        let (mob_push, sender) =
+            Self::new_with_error_sink(client, manage, config, buff_size, error_sink);
+        (mob_push, sender, err_rx)
+    }
+
+    /// 创建推送器, 推送异常发送到指定的 [`ErrorSink`]
+    pub fn new_with_error_sink(
+        client: C,
+        manage: M,
+        config: impl Into<Arc<MobPushConfig>>,
+        buff_size: usize,
+        error_sink: ErrorSink<MobPushError<M, C>>,
+    ) -> (Self, mpsc::Sender<M::PushData>) {
         let (rx, tx) = mpsc::channel(buff_size);
-        let (err_rx, err_tx) = mpsc::channel(16);
         (
             Self {
-                manage,
                 income_channel: tx,
-                error_send: err_rx,
-                client,
+                entity_concurrency: 1,
+                rate_limit: None,
+                worker: PushWorker {
+                    manage,
+                    client,
+                    config: ConfigRef::new(config),
+                    error_sink,
+                    result_send: None,
+                    retry_policy: RetryPolicy::default(),
+                    rate_limiter: RateLimiter::new(RateLimit::default()),
+                    batch_concurrency: 1,
+                    shutdown: ShutdownSignal::new(),
+                    outbox: None,
+                    dedup: None,
+                    subscribe_check: None,
+                    quiet_hours: None,
+                },
             },
             rx,
-            err_tx,
         )
     }
+
+    /// 获取推送结果接收端
+    ///
+    /// 每条消息推送完成后, 其全部推送批次的信息将发送到该接收端,
+    /// 接收端被丢弃后将不再发送推送结果
+    pub fn subscribe_result(
+        &mut self,
+        buff_size: usize,
+    ) -> mpsc::Receiver<PushResult<M::PushData>> {
+        let (tx, rx) = mpsc::channel(buff_size);
+        self.worker.result_send = Some(tx);
+        rx
+    }
+
+    /// 替换推送器使用的配置
+    ///
+    /// 可为多个推送器设置不同的配置以推送到多个 Mob 应用
+    pub fn set_config(&mut self, config: impl Into<Arc<MobPushConfig>>) -> &mut Self {
+        self.worker.config = ConfigRef::new(config);
+        self
+    }
+
+    /// 设置推送请求失败时的重试策略
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) -> &mut Self {
+        self.worker.retry_policy = retry_policy;
+        self
+    }
+
+    /// 设置推送请求的速率限制, 包括重试在内的全部推送请求共享该限制
+    ///
+    /// 未设置时使用 [`MobPushConfig::rate_limit`], 默认每秒 2 次请求。
+    /// 速率限制无效时返回异常, 见 [`RateLimit::validate`]
+    pub fn set_rate_limit(&mut self, rate_limit: RateLimit) -> Result<&mut Self, ConfigError> {
+        rate_limit.validate()?;
+        self.rate_limit = Some(rate_limit);
+        Ok(self)
+    }
+
+    /// 设置推送的并发数
+    ///
+    /// - `entities` 同时推送的消息数量
+    /// - `batches` 单条消息同时发送的推送批次数量
+    ///
+    /// 默认均为 1, 即依次推送。并发数为 0 时视为 1
+    pub fn set_concurrency(&mut self, entities: usize, batches: usize) -> &mut Self {
+        self.entity_concurrency = entities.max(1);
+        self.worker.batch_concurrency = batches.max(1);
+        self
+    }
+
+    /// 设置推送消息的持久化队列
+    ///
+    /// 接收的消息与每个批次的推送进度将记录到该队列,
+    /// 启动时恢复上次未完成的消息, 已被 Mob 接受的批次不会再次推送。
+    /// 关闭时未完成的消息同样记录到该队列, 不再出现在 [`ShutdownSummary`] 中
+    pub fn set_outbox(&mut self, outbox: impl Outbox<M::PushData>) -> &mut Self {
+        self.worker.outbox = Some(Box::new(outbox));
+        self
+    }
+
+    /// 设置本地去重窗口
+    ///
+    /// 提供了 [`PushEntity::identity`](crate::PushEntity::identity) 的消息推送完成后,
+    /// 窗口期内相同标识的消息将被跳过; 正在推送的相同标识的消息同样被跳过
+    pub fn set_dedup_window(&mut self, window: Duration) -> &mut Self {
+        self.worker.dedup = Some(DedupWindow::new(window));
+        self
+    }
+
+    /// 启用推送前逐个用户检查订阅
+    ///
+    /// 获取的订阅用户在分批前将按照 [`SubscribeCheck`] 检查, 未订阅当前消息数据源的用户将被跳过。
+    /// 消息直接指定了 [`PushEntity::push_target`](crate::PushEntity::push_target) 时不做检查
+    pub fn set_subscribe_check(&mut self, check: SubscribeCheck) -> &mut Self {
+        self.worker.subscribe_check = Some(SubscribeChecker::new(check));
+        self
+    }
+
+    /// 启用用户的免打扰时段
+    ///
+    /// 获取的订阅用户中, [`UserMobId::quiet_hours`](crate::UserMobId::quiet_hours)
+    /// 表明处于免打扰时段的用户将按照 [`QuietHoursMode`] 单独分批推送, 其余用户照常推送。
+    /// 消息直接指定了 [`PushEntity::push_target`](crate::PushEntity::push_target) 时不做处理
+    pub fn set_quiet_hours(&mut self, mode: QuietHoursMode) -> &mut Self {
+        self.worker.quiet_hours = Some(mode);
+        self
+    }
+
+    /// 获取推送器的关闭句柄
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.worker.shutdown.handle()
+    }
 }
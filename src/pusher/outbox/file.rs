@@ -0,0 +1,574 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{Area, Platform, Platforms, PushTarget, Schedule, TagsCombine};
+
+use super::{Outbox, OutboxEntry, OutboxId, PlannedBatch, PlannedDelivery};
+
+/// 基于本地文件的 [`Outbox`], 消息需要支持序列化与反序列化
+///
+/// 每次变更以一行 JSON 追加到文件末尾, 并在写入后同步到磁盘,
+/// 文件读写在阻塞线程中执行。
+/// 打开时与记录数量超过阈值时压缩文件, 全部消息推送完成后清空文件
+pub struct FileOutbox<T> {
+    path: PathBuf,
+    state: Arc<Mutex<FileState>>,
+    _data: PhantomData<fn() -> T>,
+}
+
+/// 触发压缩的最少记录数量
+const COMPACT_THRESHOLD: usize = 1024;
+
+impl<T> std::fmt::Debug for FileOutbox<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileOutbox")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl<T> FileOutbox<T> {
+    /// 打开指定路径的持久化队列, 文件不存在时将被创建
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut entries = BTreeMap::new();
+        let mut next_id = 1;
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    // 写入中断时最后一行可能不完整
+                    match serde_json::from_str::<Record>(&line) {
+                        Ok(record) => {
+                            next_id = next_id.max(record.id() + 1);
+                            record.apply(&mut entries);
+                        }
+                        Err(err) => warn!(event = "Skip Broken Outbox Record", error = %err),
+                    }
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        let (file, records) = compact(&path, &entries)?;
+        Ok(Self {
+            state: Arc::new(Mutex::new(FileState {
+                file,
+                path: path.clone(),
+                records,
+                compact_at: compact_at(records),
+                next_id,
+                entries,
+            })),
+            path,
+            _data: PhantomData,
+        })
+    }
+
+    /// 持久化队列文件的路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 尚未完成推送的消息数量
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FileState> {
+        lock(&self.state)
+    }
+
+    /// 在阻塞线程中追加记录
+    async fn append(&self, record: Record) -> io::Result<()> {
+        let state = Arc::clone(&self.state);
+        tokio::task::spawn_blocking(move || lock(&state).append(&record))
+            .await
+            .map_err(io::Error::other)?
+    }
+}
+
+fn lock(state: &Mutex<FileState>) -> MutexGuard<'_, FileState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[async_trait]
+impl<T> Outbox<T> for FileOutbox<T>
+where
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    type Error = io::Error;
+
+    async fn accept(&self, data: &T) -> io::Result<OutboxId> {
+        let data = serde_json::to_value(data)?;
+        let state = Arc::clone(&self.state);
+        tokio::task::spawn_blocking(move || {
+            let mut state = lock(&state);
+            let id = state.next_id;
+            state.append(&Record::Accept { id, data })?;
+            state.next_id += 1;
+            Ok(id)
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+
+    async fn plan(&self, id: OutboxId, batches: &[PlannedBatch]) -> io::Result<()> {
+        self.append(Record::Plan {
+            id,
+            batches: batches.iter().map(StoredBatch::from).collect(),
+        })
+        .await
+    }
+
+    async fn planned(&self, id: OutboxId) -> io::Result<()> {
+        self.append(Record::Planned { id }).await
+    }
+
+    async fn ack(&self, id: OutboxId, index: usize) -> io::Result<()> {
+        self.append(Record::Ack { id, index }).await
+    }
+
+    async fn complete(&self, id: OutboxId) -> io::Result<()> {
+        self.append(Record::Complete { id }).await
+    }
+
+    async fn recover(&self) -> io::Result<Vec<OutboxEntry<T>>> {
+        let state = self.lock();
+        state
+            .entries
+            .iter()
+            .map(|(id, entry)| {
+                Ok(OutboxEntry {
+                    id: *id,
+                    data: serde_json::from_value(entry.data.clone())?,
+                    plan: entry.plan.iter().cloned().map(PlannedBatch::from).collect(),
+                    planned: entry.planned,
+                    acked: entry.acked.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+struct FileState {
+    file: File,
+    path: PathBuf,
+    /// 文件中的记录数量
+    records: usize,
+    /// 记录数量达到该值时压缩文件
+    compact_at: usize,
+    next_id: OutboxId,
+    entries: BTreeMap<OutboxId, StoredEntry>,
+}
+
+impl FileState {
+    /// 追加记录并应用到内存状态, 全部消息完成后清空文件
+    fn append(&mut self, record: &Record) -> io::Result<()> {
+        write_record(&mut self.file, record)?;
+        self.file.sync_data()?;
+        self.records += 1;
+        record.apply(&mut self.entries);
+
+        if self.entries.is_empty() {
+            self.file.set_len(0)?;
+            self.file.sync_data()?;
+            self.records = 0;
+        } else if self.records >= self.compact_at {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// 仅保留未完成的消息重写文件
+    fn compact(&mut self) -> io::Result<()> {
+        let (file, records) = compact(&self.path, &self.entries)?;
+        self.file = file;
+        self.records = records;
+        self.compact_at = compact_at(records);
+        Ok(())
+    }
+}
+
+/// 压缩后的记录数量翻倍或达到阈值时再次压缩, 避免未完成的消息较多时频繁重写
+fn compact_at(records: usize) -> usize {
+    COMPACT_THRESHOLD.max(records * 2)
+}
+
+fn write_record(file: &mut File, record: &Record) -> io::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+/// 仅保留未完成的消息重写文件, 返回以追加方式打开的文件与其中的记录数量
+fn compact(path: &Path, entries: &BTreeMap<OutboxId, StoredEntry>) -> io::Result<(File, usize)> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_owned();
+    tmp_name.push(".compact");
+    let tmp = path.with_file_name(tmp_name);
+
+    let mut file = File::create(&tmp)?;
+    let mut records = 0;
+    for (id, entry) in entries {
+        for record in entry.records(*id) {
+            write_record(&mut file, &record)?;
+            records += 1;
+        }
+    }
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+
+    Ok((OpenOptions::new().append(true).open(path)?, records))
+}
+
+#[derive(Debug, Clone)]
+struct StoredEntry {
+    data: Value,
+    plan: Vec<StoredBatch>,
+    planned: bool,
+    acked: BTreeSet<usize>,
+}
+
+impl StoredEntry {
+    fn records(&self, id: OutboxId) -> Vec<Record> {
+        let mut records = vec![Record::Accept {
+            id,
+            data: self.data.clone(),
+        }];
+        if !self.plan.is_empty() {
+            records.push(Record::Plan {
+                id,
+                batches: self.plan.clone(),
+            });
+        }
+        if self.planned {
+            records.push(Record::Planned { id });
+        }
+        records.extend(self.acked.iter().map(|&index| Record::Ack { id, index }));
+        records
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum Record {
+    Accept {
+        id: OutboxId,
+        data: Value,
+    },
+    Plan {
+        id: OutboxId,
+        batches: Vec<StoredBatch>,
+    },
+    Planned {
+        id: OutboxId,
+    },
+    Ack {
+        id: OutboxId,
+        index: usize,
+    },
+    Complete {
+        id: OutboxId,
+    },
+}
+
+impl Record {
+    fn id(&self) -> OutboxId {
+        match self {
+            Record::Accept { id, .. }
+            | Record::Plan { id, .. }
+            | Record::Planned { id }
+            | Record::Ack { id, .. }
+            | Record::Complete { id } => *id,
+        }
+    }
+
+    fn apply(&self, entries: &mut BTreeMap<OutboxId, StoredEntry>) {
+        match self {
+            Record::Accept { id, data } => {
+                entries.insert(
+                    *id,
+                    StoredEntry {
+                        data: data.clone(),
+                        plan: Vec::new(),
+                        planned: false,
+                        acked: BTreeSet::new(),
+                    },
+                );
+            }
+            Record::Plan { id, batches } => {
+                if let Some(entry) = entries.get_mut(id) {
+                    entry.plan.extend(batches.iter().cloned());
+                }
+            }
+            Record::Planned { id } => {
+                if let Some(entry) = entries.get_mut(id) {
+                    entry.planned = true;
+                }
+            }
+            Record::Ack { id, index } => {
+                if let Some(entry) = entries.get_mut(id) {
+                    entry.acked.insert(*index);
+                }
+            }
+            Record::Complete { id } => {
+                entries.remove(id);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredBatch {
+    target: StoredTarget,
+    platforms: Vec<i32>,
+    passthrough: bool,
+    #[serde(default)]
+    delivery: StoredDelivery,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum StoredDelivery {
+    #[default]
+    Normal,
+    /// 保留时区信息, 以 RFC 3339 格式记录
+    Deferred {
+        at: String,
+    },
+    Silent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum StoredTarget {
+    All,
+    Alias {
+        alias: Vec<String>,
+    },
+    Tags {
+        tags: Vec<String>,
+        and: bool,
+    },
+    Area {
+        country: Option<String>,
+        province: Option<String>,
+        city: Option<String>,
+    },
+    Rids {
+        rids: Vec<String>,
+    },
+}
+
+impl From<&PlannedBatch> for StoredBatch {
+    fn from(batch: &PlannedBatch) -> Self {
+        let target = match batch.target.clone() {
+            PushTarget::All => StoredTarget::All,
+            PushTarget::Alias(alias) => StoredTarget::Alias { alias },
+            PushTarget::Tags { tags, combine } => StoredTarget::Tags {
+                tags,
+                and: combine == TagsCombine::And,
+            },
+            PushTarget::Area(Area {
+                country,
+                province,
+                city,
+            }) => StoredTarget::Area {
+                country,
+                province,
+                city,
+            },
+            PushTarget::Rids(rids) => StoredTarget::Rids { rids },
+        };
+        Self {
+            target,
+            platforms: batch.platforms.iter().map(Platform::to_code).collect(),
+            passthrough: batch.passthrough,
+            delivery: match &batch.delivery {
+                PlannedDelivery::Normal => StoredDelivery::Normal,
+                PlannedDelivery::Deferred(schedule) => StoredDelivery::Deferred {
+                    at: schedule.time().to_rfc3339(),
+                },
+                PlannedDelivery::Silent => StoredDelivery::Silent,
+            },
+        }
+    }
+}
+
+impl From<StoredBatch> for PlannedBatch {
+    fn from(batch: StoredBatch) -> Self {
+        let target = match batch.target {
+            StoredTarget::All => PushTarget::All,
+            StoredTarget::Alias { alias } => PushTarget::Alias(alias),
+            StoredTarget::Tags { tags, and } => PushTarget::Tags {
+                tags,
+                combine: if and {
+                    TagsCombine::And
+                } else {
+                    TagsCombine::Or
+                },
+            },
+            StoredTarget::Area {
+                country,
+                province,
+                city,
+            } => PushTarget::Area(Area {
+                country,
+                province,
+                city,
+            }),
+            StoredTarget::Rids { rids } => PushTarget::Rids(rids),
+        };
+        Self {
+            target,
+            platforms: batch
+                .platforms
+                .into_iter()
+                .filter_map(Platform::from_code)
+                .collect::<Platforms>(),
+            passthrough: batch.passthrough,
+            delivery: match batch.delivery {
+                StoredDelivery::Normal => PlannedDelivery::Normal,
+                StoredDelivery::Deferred { at } => match DateTime::parse_from_rfc3339(&at) {
+                    // 恢复时已过定时推送时间则立即推送
+                    Ok(time) => Schedule::new_at(time)
+                        .map(PlannedDelivery::Deferred)
+                        .unwrap_or_default(),
+                    Err(err) => {
+                        // 无法还原定时推送时间时立即推送
+                        warn!(event = "Broken Deferred Schedule", at, error = %err);
+                        PlannedDelivery::Normal
+                    }
+                },
+                StoredDelivery::Silent => PlannedDelivery::Silent,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeSet,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use chrono::{FixedOffset, TimeZone};
+
+    use crate::{Platform, Platforms, PushTarget, Schedule, TagsCombine};
+
+    use super::{FileOutbox, Outbox, PlannedBatch, PlannedDelivery, COMPACT_THRESHOLD};
+
+    #[tokio::test]
+    async fn test_resume() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("mob-push-outbox-{nanos}.jsonl"));
+
+        let deferred = Schedule::new_at(
+            FixedOffset::east_opt(8 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2099, 3, 2, 8, 0, 0)
+                .unwrap(),
+        )
+        .unwrap();
+        let outbox = FileOutbox::<String>::open(&path).unwrap();
+        let first = outbox.accept(&"first".to_owned()).await.unwrap();
+        let second = outbox.accept(&"second".to_owned()).await.unwrap();
+        outbox
+            .plan(
+                first,
+                &[
+                    PlannedBatch {
+                        target: PushTarget::new_rids(["a"]),
+                        platforms: Platforms::only(Platform::Ios),
+                        passthrough: false,
+                        delivery: PlannedDelivery::Deferred(deferred.clone()),
+                    },
+                    PlannedBatch {
+                        target: PushTarget::new_tags(["b"], TagsCombine::And),
+                        platforms: Platforms::default(),
+                        passthrough: true,
+                        delivery: PlannedDelivery::Silent,
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        outbox.ack(first, 0).await.unwrap();
+        outbox.complete(second).await.unwrap();
+        drop(outbox);
+
+        // 重新打开后恢复未完成的消息
+        let outbox = FileOutbox::<String>::open(&path).unwrap();
+        let entries = outbox.recover().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, first);
+        assert_eq!(entries[0].data, "first");
+        assert_eq!(entries[0].acked, BTreeSet::from([0]));
+        assert!(!entries[0].planned);
+        let plan = &entries[0].plan;
+        assert_eq!(plan[0].platforms, Platforms::only(Platform::Ios));
+        assert!(
+            matches!(&plan[1].target, PushTarget::Tags { combine, .. } if *combine == TagsCombine::And)
+        );
+        assert!(plan[1].passthrough);
+        assert_eq!(plan[0].delivery, PlannedDelivery::Deferred(deferred));
+        assert_eq!(plan[1].delivery, PlannedDelivery::Silent);
+
+        // 新消息的 ID 不与已有消息重复
+        assert!(outbox.accept(&"third".to_owned()).await.unwrap() > second);
+        outbox.complete(first).await.unwrap();
+        outbox.complete(second + 1).await.unwrap();
+        assert!(outbox.is_empty());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_compact() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("mob-push-outbox-{nanos}.jsonl"));
+
+        let outbox = FileOutbox::<String>::open(&path).unwrap();
+        let id = outbox.accept(&"first".to_owned()).await.unwrap();
+        for _ in 0..COMPACT_THRESHOLD {
+            outbox.ack(id, 0).await.unwrap();
+        }
+        // 达到阈值后压缩, 重复的确认记录被合并
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines < COMPACT_THRESHOLD, "{lines} records left");
+        assert_eq!(
+            outbox.recover().await.unwrap()[0].acked,
+            BTreeSet::from([0])
+        );
+
+        std::fs::remove_file(&path).ok();
+    }
+}
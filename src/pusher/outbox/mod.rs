@@ -0,0 +1,129 @@
+mod file;
+
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+
+use crate::{Platforms, PushTarget, Schedule};
+
+pub use self::file::FileOutbox;
+
+/// 持久化队列中消息的 ID
+pub type OutboxId = u64;
+
+/// 一条消息规划的单个推送批次
+#[derive(Debug, Clone)]
+pub struct PlannedBatch {
+    /// 推送目标
+    pub target: PushTarget,
+    /// 推送平台
+    pub platforms: Platforms,
+    /// 是否为透传消息批次
+    pub passthrough: bool,
+    /// 批次的送达方式
+    pub delivery: PlannedDelivery,
+}
+
+/// 推送批次的送达方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PlannedDelivery {
+    /// 按照消息自身的配置推送
+    #[default]
+    Normal,
+    /// 处于免打扰时段的用户, 定时到时段结束时推送
+    Deferred(Schedule),
+    /// 处于免打扰时段的用户, 立即推送静默通知
+    Silent,
+}
+
+/// 持久化队列中尚未完成推送的消息
+#[derive(Debug)]
+pub struct OutboxEntry<T> {
+    pub id: OutboxId,
+    pub data: T,
+    /// 已规划的推送批次
+    pub plan: Vec<PlannedBatch>,
+    /// 是否已规划全部推送批次
+    ///
+    /// 为 `false` 时将重新获取订阅用户, 已规划批次中的用户将被跳过
+    pub planned: bool,
+    /// 已被 Mob 接受的批次在 `plan` 中的序号
+    pub acked: BTreeSet<usize>,
+}
+
+/// 推送消息的持久化队列
+///
+/// 推送器通过 [`MobPusher::set_outbox`](crate::MobPusher::set_outbox) 启用后,
+/// 记录接收的消息与每个批次的推送进度。
+/// 启动时恢复未完成的消息, 已被 Mob 接受的批次不会再次推送
+#[async_trait]
+pub trait Outbox<T>: 'static + Send + Sync {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// 记录一条新消息, 返回该消息的 ID
+    async fn accept(&self, data: &T) -> Result<OutboxId, Self::Error>;
+
+    /// 记录消息新规划的推送批次, 批次序号接续已记录的批次
+    async fn plan(&self, id: OutboxId, batches: &[PlannedBatch]) -> Result<(), Self::Error>;
+
+    /// 记录消息已规划全部推送批次
+    async fn planned(&self, id: OutboxId) -> Result<(), Self::Error>;
+
+    /// 记录消息的第 `index` 个批次已被 Mob 接受
+    async fn ack(&self, id: OutboxId, index: usize) -> Result<(), Self::Error>;
+
+    /// 消息推送完成, 无论成功与否, 之后不再恢复
+    async fn complete(&self, id: OutboxId) -> Result<(), Self::Error>;
+
+    /// 获取全部尚未完成推送的消息
+    async fn recover(&self) -> Result<Vec<OutboxEntry<T>>, Self::Error>;
+}
+
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 擦除异常类型的 [`Outbox`], 以便推送器持有
+#[async_trait]
+pub(crate) trait DynOutbox<T>: Send + Sync {
+    async fn accept(&self, data: &T) -> Result<OutboxId, BoxError>;
+
+    async fn plan(&self, id: OutboxId, batches: &[PlannedBatch]) -> Result<(), BoxError>;
+
+    async fn planned(&self, id: OutboxId) -> Result<(), BoxError>;
+
+    async fn ack(&self, id: OutboxId, index: usize) -> Result<(), BoxError>;
+
+    async fn complete(&self, id: OutboxId) -> Result<(), BoxError>;
+
+    async fn recover(&self) -> Result<Vec<OutboxEntry<T>>, BoxError>;
+}
+
+#[async_trait]
+impl<T, O> DynOutbox<T> for O
+where
+    T: Sync + 'static,
+    O: Outbox<T>,
+{
+    async fn accept(&self, data: &T) -> Result<OutboxId, BoxError> {
+        Ok(Outbox::accept(self, data).await?)
+    }
+
+    async fn plan(&self, id: OutboxId, batches: &[PlannedBatch]) -> Result<(), BoxError> {
+        Ok(Outbox::plan(self, id, batches).await?)
+    }
+
+    async fn planned(&self, id: OutboxId) -> Result<(), BoxError> {
+        Ok(Outbox::planned(self, id).await?)
+    }
+
+    async fn ack(&self, id: OutboxId, index: usize) -> Result<(), BoxError> {
+        Ok(Outbox::ack(self, id, index).await?)
+    }
+
+    async fn complete(&self, id: OutboxId) -> Result<(), BoxError> {
+        Ok(Outbox::complete(self, id).await?)
+    }
+
+    async fn recover(&self) -> Result<Vec<OutboxEntry<T>>, BoxError> {
+        Ok(Outbox::recover(self).await?)
+    }
+}
@@ -3,47 +3,16 @@ use std::borrow::Cow;
 use serde::{ser::SerializeStruct, Serialize};
 
 use crate::{
-    config::get_config,
+    message_kind::NotifyType,
     push_notify::{
-        android::AndroidNotify, ios::IosNotify, Notify, NotifySerialize, SerializeInformation,
+        android::{AndroidNotify, FactoryExtra},
+        extras::ExtrasMapList,
+        ios::IosNotify,
+        Extras, Notify, NotifySerialize, SerializeInformation,
     },
-    user_subscribe::UserMobId,
-    PushEntity, PushForward,
+    Platform, Platforms, PushEntity, PushForward, PushTarget, Schedule,
 };
 
-pub struct PushTarget {
-    pub target_user: Vec<String>,
-}
-
-impl PushTarget {
-    pub fn new(user_iter: &mut impl Iterator<Item = impl UserMobId>) -> Option<Self> {
-        let vec = user_iter
-            .take(1000)
-            .map(|user| user.get_mob_id().to_string())
-            .collect::<Vec<_>>();
-
-        if vec.is_empty() {
-            None
-        } else {
-            Some(Self { target_user: vec })
-        }
-    }
-}
-
-impl Serialize for PushTarget {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let mut push_target = serializer.serialize_struct("pushTarget", 2)?;
-
-        push_target.serialize_field("target", &4)?;
-        push_target.serialize_field("rids", &self.target_user)?;
-
-        push_target.end()
-    }
-}
-
 pub struct Forward(PushForward);
 
 impl Forward {
@@ -75,22 +44,67 @@ where
     title: Cow<'p, str>,
     android_notify: A,
     ios_notify: I,
+    schedule: Option<Schedule>,
+    notify_type: NotifyType<'p>,
+    extras: Extras,
+    platforms: Platforms,
+    ios_production: bool,
 }
 
 impl<'p> PushNotify<'p> {
-    pub fn new_with_builder<T: PushEntity>(data: &'p T) -> Self {
+    pub fn new_with_builder<T: PushEntity>(
+        data: &'p T,
+        notify_type: NotifyType<'p>,
+        platforms: Platforms,
+    ) -> Self {
         let mut android_notify = AndroidNotify::default().into_notify();
         data.android_notify(&mut android_notify);
         let mut ios_notify = IosNotify::default().into_notify();
         data.ios_notify(&mut ios_notify);
+        let mut extras = Extras::new();
+        data.extras(&mut extras);
 
         Self {
             body: data.get_send_content().as_ref(),
             title: data.get_title(),
             android_notify,
             ios_notify,
+            schedule: data.schedule(),
+            notify_type,
+            extras,
+            platforms,
+            ios_production: true,
         }
     }
+
+    /// 设置 iOS 推送是否使用生产环境
+    pub(crate) fn set_ios_production(&mut self, ios_production: bool) -> &mut Self {
+        self.ios_production = ios_production;
+        self
+    }
+
+    /// 覆盖消息自身的定时推送时间
+    pub(crate) fn set_schedule(&mut self, schedule: Schedule) -> &mut Self {
+        self.schedule.replace(schedule);
+        self
+    }
+
+    /// 以静默通知推送
+    pub(crate) fn set_silent(&mut self) -> &mut Self {
+        self.android_notify.mute();
+        self.ios_notify.mute();
+        self
+    }
+}
+
+impl<'p> PushNotify<'p> {
+    /// 仅推送到安卓平台时附带厂商通道配置
+    fn factory_extra(&self) -> Option<&FactoryExtra> {
+        self.platforms
+            .contains(Platform::Android)
+            .then(|| self.android_notify.factory_extra())
+            .flatten()
+    }
 }
 
 impl<'p> Serialize for PushNotify<'p> {
@@ -98,26 +112,66 @@ impl<'p> Serialize for PushNotify<'p> {
     where
         S: serde::Serializer,
     {
-        let mut len = 4;
-        if self.android_notify.need_serialize() {
+        if self.platforms.is_empty() {
+            return Err(serde::ser::Error::custom("push platforms is empty"));
+        }
+        let (android_notify, ios_notify) = if self.notify_type.is_passthrough() {
+            (
+                Cow::Owned(self.android_notify.passthrough_only().into_notify()),
+                Cow::Owned(self.ios_notify.passthrough_only().into_notify()),
+            )
+        } else {
+            (
+                Cow::Borrowed(&self.android_notify),
+                Cow::Borrowed(&self.ios_notify),
+            )
+        };
+        // 仅向包含对应平台的推送附带平台配置
+        let android_notify = (self.platforms.contains(Platform::Android)
+            && android_notify.need_serialize())
+        .then_some(android_notify);
+        let ios_notify = (self.platforms.contains(Platform::Ios) && ios_notify.need_serialize())
+            .then_some(ios_notify);
+
+        let mut extras = ExtrasMapList::new(&self.extras);
+        if let NotifyType::Passthrough(payload) = self.notify_type {
+            extras.extend(payload);
+        }
+        extras.check().map_err(serde::ser::Error::custom)?;
+
+        let mut len = 4 + self.schedule.serialize_field();
+        if !extras.is_empty() {
+            len += 1;
+        }
+        if android_notify.is_some() {
             len += 1;
         }
-        if self.ios_notify.need_serialize() {
+        if self.platforms.contains(Platform::Ios) {
+            len += 1;
+        }
+        if ios_notify.is_some() {
             len += 1;
         }
 
         let mut notify = serializer.serialize_struct("PushNotify", len)?;
 
-        notify.serialize_field("plats", &[1, 2])?;
+        notify.serialize_field("plats", &self.platforms)?;
         notify.serialize_field("content", &self.body)?;
-        notify.serialize_field("type", &1)?;
+        notify.serialize_field("type", &self.notify_type.to_code())?;
         notify.serialize_field("title", &self.title)?;
+        NotifySerialize::serialize::<S>(&self.schedule, &mut notify)?;
 
-        if self.android_notify.need_serialize() {
-            notify.serialize_field("androidNotify", &self.android_notify)?;
+        if self.platforms.contains(Platform::Ios) {
+            notify.serialize_field("iosProduction", &i32::from(self.ios_production))?;
+        }
+        if !extras.is_empty() {
+            notify.serialize_field("extrasMapList", &extras)?;
+        }
+        if let Some(android_notify) = android_notify {
+            notify.serialize_field("androidNotify", &android_notify)?;
         }
-        if self.ios_notify.need_serialize() {
-            notify.serialize_field("iosNotify", &self.ios_notify)?;
+        if let Some(ios_notify) = ios_notify {
+            notify.serialize_field("iosNotify", &ios_notify)?;
         }
 
         notify.end()
@@ -125,9 +179,11 @@ impl<'p> Serialize for PushNotify<'p> {
 }
 
 pub(crate) struct CreatePush<'p> {
+    pub app_key: &'p str,
     pub push_target: PushTarget,
     pub push_notify: PushNotify<'p>,
     pub push_forward: Forward,
+    pub workno: Option<String>,
 }
 
 impl<'p> Serialize for CreatePush<'p> {
@@ -135,61 +191,203 @@ impl<'p> Serialize for CreatePush<'p> {
     where
         S: serde::Serializer,
     {
-        let mut push_body = serializer.serialize_struct("CreatePush", 4)?;
+        let factory_extra = self.push_notify.factory_extra();
+        let len = 5 + factory_extra.is_some() as usize + self.workno.is_some() as usize;
+        let mut push_body = serializer.serialize_struct("CreatePush", len)?;
 
         push_body.serialize_field("source", &"webapi")?;
-        push_body.serialize_field("appkey", &get_config().key)?;
+        push_body.serialize_field("appkey", self.app_key)?;
         push_body.serialize_field("pushTarget", &self.push_target)?;
         push_body.serialize_field("pushNotify", &self.push_notify)?;
         push_body.serialize_field("pushForward", &self.push_forward)?;
+        if let Some(factory_extra) = factory_extra {
+            push_body.serialize_field("pushFactoryExtra", factory_extra)?;
+        }
+        if let Some(workno) = &self.workno {
+            push_body.serialize_field("workno", workno)?;
+        }
 
         push_body.end()
     }
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub(crate) struct Respond {
-    pub(crate) status: u16,
-    #[serde(rename = "res")]
-    pub(crate) _res: Option<ResBody>,
-    pub(crate) error: Option<String>,
-}
 #[derive(Debug, serde::Deserialize)]
 pub(crate) struct ResBody {
     #[serde(rename = "batchId")]
-    pub(crate) _batch_id: String,
+    pub(crate) batch_id: String,
 }
 
 #[cfg(test)]
 mod test_serde {
     use std::borrow::Cow;
 
+    use chrono::{FixedOffset, TimeZone};
+
     use crate::{
-        config::load_from_test,
-        push_notify::{android::AndroidNotify, ios::IosNotify, SerializeInformation},
+        message_kind::NotifyType,
+        push_notify::{
+            android::{sound::WarnSound, AndroidNotify, Badge},
+            ios::{content_avaliable::ContentAvailable, IosBadgeType, IosNotify, IosPushSound},
+            Extras, SerializeInformation,
+        },
+        MobPushConfig, Platform, Platforms, PushTarget, Schedule,
     };
 
     use super::CreatePush;
 
     #[test]
     fn test_serde() {
-        load_from_test();
+        let config = MobPushConfig::new("test-key", "test-secret");
 
         let c = CreatePush {
-            push_target: super::PushTarget {
-                target_user: vec!["abc".to_string(), "cdde".to_string()],
-            },
+            app_key: &config.key,
+            push_target: PushTarget::new_rids(["abc", "cdde"]),
             push_notify: super::PushNotify {
                 body: &String::from(r#"{"aab":11}"#),
                 android_notify: AndroidNotify::default().into_notify(),
                 ios_notify: IosNotify::default().into_notify(),
                 title: Cow::Borrowed("12345"),
+                schedule: None,
+                notify_type: NotifyType::Notify,
+                extras: Extras::new(),
+                platforms: Platforms::default(),
+                ios_production: true,
             },
             push_forward: super::Forward(crate::PushForward::HomePage),
+            workno: None,
+        };
+
+        let value = serde_json::to_value(&c).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "appkey": "test-key",
+                "source": "webapi",
+                "pushTarget": {"target": 4, "rids": ["abc", "cdde"]},
+                "pushNotify": {
+                    "plats": [1, 2],
+                    "content": r#"{"aab":11}"#,
+                    "type": 1,
+                    "title": "12345",
+                    "iosProduction": 1
+                },
+                "pushForward": {"nextType": 0}
+            })
+        );
+    }
+
+    #[test]
+    fn test_schedule() {
+        let time = FixedOffset::east_opt(8 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2099, 1, 1, 8, 0, 0)
+            .unwrap();
+        let notify = super::PushNotify {
+            body: "body",
+            android_notify: AndroidNotify::default().into_notify(),
+            ios_notify: IosNotify::default().into_notify(),
+            title: Cow::Borrowed("title"),
+            schedule: Schedule::new_at(time),
+            notify_type: NotifyType::Notify,
+            extras: Extras::new(),
+            platforms: Platforms::default(),
+            ios_production: true,
+        };
+
+        let value = serde_json::to_value(&notify).unwrap();
+
+        assert_eq!(value["taskCron"], 1);
+        assert_eq!(value["taskTime"], 4070908800000i64);
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let payload = [("feed".to_string(), "refresh".to_string())]
+            .into_iter()
+            .collect();
+        let mut extras = Extras::new();
+        extras
+            .insert("cookie", "1")
+            .unwrap()
+            .insert("feed", "keep")
+            .unwrap();
+        let mut android_notify = AndroidNotify::default().into_notify();
+        android_notify
+            .set_badge(Badge::new_add(1))
+            .set_warn(WarnSound::Vibration.into());
+        let mut ios_notify = IosNotify::default().into_notify();
+        ios_notify
+            .set_badge(IosBadgeType::Adding(1))
+            .set_sound(IosPushSound::Default)
+            .set_subtitle("subtitle".into())
+            .set_content_available(Some(ContentAvailable));
+        let notify = super::PushNotify {
+            body: "body",
+            android_notify,
+            ios_notify,
+            title: Cow::Borrowed("title"),
+            schedule: None,
+            notify_type: NotifyType::Passthrough(&payload),
+            extras,
+            platforms: Platforms::default(),
+            ios_production: true,
         };
 
-        let string = serde_json::to_string_pretty(&c).unwrap();
+        let value = serde_json::to_value(&notify).unwrap();
+
+        assert_eq!(value["type"], 2);
+        assert_eq!(
+            value["extrasMapList"],
+            serde_json::json!([
+                {"key": "cookie", "value": "1"},
+                {"key": "feed", "value": "refresh"}
+            ])
+        );
+        assert_eq!(value["androidNotify"], serde_json::json!({"warn": "2"}));
+        assert_eq!(
+            value["iosNotify"],
+            serde_json::json!({"contentAvailable": 1})
+        );
+
+        // 透传内容与 Extras 合并后超出限制时无法序列化
+        let payload = [("feed".to_string(), "v".repeat(Extras::MAX_SIZE))]
+            .into_iter()
+            .collect();
+        let notify = super::PushNotify {
+            notify_type: NotifyType::Passthrough(&payload),
+            ..notify
+        };
+        assert!(serde_json::to_value(&notify).is_err());
+    }
 
-        println!("{string}")
+    #[test]
+    fn test_platforms() {
+        let mut android_notify = AndroidNotify::default().into_notify();
+        android_notify.set_badge(Badge::new_add(1));
+        let notify = super::PushNotify {
+            body: "body",
+            android_notify,
+            ios_notify: IosNotify::default().into_notify(),
+            title: Cow::Borrowed("title"),
+            schedule: None,
+            notify_type: NotifyType::Notify,
+            extras: Extras::new(),
+            platforms: Platforms::only(Platform::Ios),
+            ios_production: true,
+        };
+
+        let value = serde_json::to_value(&notify).unwrap();
+
+        assert_eq!(value["plats"], serde_json::json!([2]));
+        assert_eq!(value["iosProduction"], 1);
+        assert!(value.get("androidNotify").is_none());
+
+        // 不推送到任何平台的请求无法序列化
+        let notify = super::PushNotify {
+            platforms: Platforms::new([]),
+            ..notify
+        };
+        assert!(serde_json::to_value(&notify).is_err());
     }
 }
@@ -0,0 +1,36 @@
+use std::time::{Duration, SystemTime};
+
+/// 单次推送请求的结果
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    /// Mob 返回的推送批次 ID, 响应中缺少批次 ID 时为 `None`
+    pub batch_id: Option<String>,
+    /// 当前批次的推送目标数量, 广播与地理位置推送为 `None`
+    pub recipients: Option<usize>,
+    /// 发起推送请求的时间
+    pub sent_at: SystemTime,
+    /// 推送请求耗时
+    pub elapsed: Duration,
+}
+
+/// 一条推送消息的推送结果
+#[derive(Debug)]
+pub struct PushResult<T> {
+    /// 被推送的消息
+    pub data: T,
+    /// 该消息创建的全部推送批次
+    pub batches: Vec<BatchResult>,
+    /// 开始推送的时间
+    pub started_at: SystemTime,
+    /// 推送该消息的总耗时
+    pub elapsed: Duration,
+}
+
+impl<T> PushResult<T> {
+    /// 该消息创建的全部推送批次 ID, 缺少批次 ID 的批次被跳过
+    pub fn batch_ids(&self) -> impl Iterator<Item = &str> {
+        self.batches
+            .iter()
+            .filter_map(|batch| batch.batch_id.as_deref())
+    }
+}
@@ -0,0 +1,126 @@
+use std::{sync::Mutex, time::Duration};
+
+use tokio::time::{sleep, Instant};
+
+use crate::config::ConfigError;
+
+/// 推送请求的速率限制
+///
+/// 使用令牌桶算法, 全部推送中的消息与批次共享同一个令牌桶
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(default)]
+pub struct RateLimit {
+    /// 每秒产生的令牌数, 即长期平均的每秒请求数
+    pub qps: f64,
+    /// 令牌桶容量, 即允许的最大突发请求数
+    pub burst: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self { qps: 2.0, burst: 1 }
+    }
+}
+
+impl RateLimit {
+    /// 检查速率限制是否有效, `qps` 须为有限的正数, `burst` 须大于 0
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(self.qps.is_finite() && self.qps > 0.0) {
+            return Err(ConfigError::Invalid("rate_limit.qps must be positive"));
+        }
+        if self.burst == 0 {
+            return Err(ConfigError::Invalid("rate_limit.burst must be positive"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// 令牌桶速率限制器
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// `limit` 需已通过 [`RateLimit::validate`] 检查
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        debug_assert!(limit.validate().is_ok(), "invalid rate limit {limit:?}");
+        Self {
+            bucket: Mutex::new(Bucket {
+                tokens: limit.burst as f64,
+                last_refill: Instant::now(),
+            }),
+            limit,
+        }
+    }
+
+    /// 等待直到获取一个令牌
+    pub(crate) async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire() {
+            sleep(wait).await;
+        }
+    }
+
+    /// 尝试获取一个令牌, 令牌不足时返回需要等待的时间
+    fn try_acquire(&self) -> Option<Duration> {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.limit.qps).min(self.limit.burst as f64);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.limit.qps;
+            Some(Duration::from_secs_f64(wait.min(u32::MAX as f64)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::{RateLimit, RateLimiter};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit() {
+        let limiter = RateLimiter::new(RateLimit {
+            qps: 10.0,
+            burst: 5,
+        });
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        for _ in 0..10 {
+            limiter.acquire().await;
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(990), "{elapsed:?}");
+        assert!(elapsed <= Duration::from_millis(1100), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RateLimit::default().validate().is_ok());
+        for qps in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(RateLimit { qps, burst: 1 }.validate().is_err(), "{qps}");
+        }
+        assert!(RateLimit { qps: 1.0, burst: 0 }.validate().is_err());
+    }
+}
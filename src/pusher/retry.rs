@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+/// 推送请求失败时的重试策略
+///
+/// 仅对可重试的异常进行重试, 参见 [`MobPushError::is_retryable`](crate::MobPushError::is_retryable)
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 单个批次的最大尝试次数, 包含首次请求
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间, 之后每次翻倍
+    pub base_delay: Duration,
+    /// 重试等待时间的上限
+    pub max_delay: Duration,
+    /// 被 Mob 限流时的最短等待时间
+    pub rate_limit_delay: Duration,
+    /// 是否为等待时间添加随机抖动
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            rate_limit_delay: Duration::from_secs(1),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 不进行重试
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// 第 `attempt` 次尝试失败后的等待时间, `attempt` 从 1 开始
+    pub(crate) fn delay(&self, attempt: u32, rate_limited: bool) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        let mut delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        if self.jitter {
+            // 每次调用独立取随机数, 在 [delay / 2, delay] 内随机
+            delay = delay / 2 + delay.mul_f64(fastrand::f64() / 2.0);
+        }
+        if rate_limited {
+            delay = delay.max(self.rate_limit_delay);
+        }
+        delay
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy {
+            jitter: false,
+            max_delay: Duration::from_secs(2),
+            ..Default::default()
+        };
+
+        assert_eq!(policy.delay(1, false), Duration::from_millis(500));
+        assert_eq!(policy.delay(2, false), Duration::from_secs(1));
+        assert_eq!(policy.delay(5, false), Duration::from_secs(2));
+        assert_eq!(policy.delay(1, true), Duration::from_secs(1));
+
+        let policy = RetryPolicy::default();
+        for attempt in 1..10 {
+            let delay = policy.delay(attempt, false);
+            assert!(delay <= policy.max_delay);
+            assert!(delay >= policy.base_delay / 2);
+        }
+    }
+}
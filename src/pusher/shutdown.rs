@@ -0,0 +1,192 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{
+    sync::watch,
+    time::{sleep_until, Instant},
+};
+
+use crate::PushTarget;
+
+use super::BatchResult;
+
+/// 推送器的关闭句柄, 通过 [`MobPusher::shutdown_handle`](super::MobPusher::shutdown_handle) 获取
+///
+/// 触发关闭后, 推送器不再接收新的消息,
+/// 正在推送的消息将在期限内继续推送剩余批次, 期限到达后未完成的批次将被记录到 [`ShutdownSummary`]
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle(Arc<watch::Sender<Option<Deadline>>>);
+
+/// 关闭期限, `None` 表示等待正在推送的消息全部完成
+type Deadline = Option<Instant>;
+
+impl ShutdownHandle {
+    /// 触发关闭, 正在推送的消息至多继续推送 `grace` 时长
+    ///
+    /// `grace` 过长以至于无法表示时, 等待正在推送的消息全部完成。
+    /// 重复调用时以最早的期限为准
+    pub fn shutdown(&self, grace: Duration) {
+        let deadline = Instant::now().checked_add(grace);
+        self.0
+            .send_if_modified(|current| match (*current, deadline) {
+                (Some(_), None) => false,
+                (Some(Some(current)), Some(deadline)) if current <= deadline => false,
+                _ => {
+                    *current = Some(deadline);
+                    true
+                }
+            });
+    }
+
+    /// 立即关闭, 不再发送任何推送批次
+    pub fn shutdown_now(&self) {
+        self.shutdown(Duration::ZERO)
+    }
+
+    /// 是否已经触发关闭
+    pub fn is_shutdown(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+}
+
+/// 推送器内部监听关闭信号的一端
+#[derive(Debug)]
+pub(crate) struct ShutdownSignal {
+    handle: ShutdownHandle,
+    receiver: watch::Receiver<Option<Deadline>>,
+}
+
+impl ShutdownSignal {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = watch::channel(None);
+        Self {
+            handle: ShutdownHandle(Arc::new(sender)),
+            receiver,
+        }
+    }
+
+    pub(crate) fn handle(&self) -> ShutdownHandle {
+        self.handle.clone()
+    }
+
+    /// 等待直到触发关闭
+    pub(crate) async fn triggered(&self) {
+        let mut receiver = self.receiver.clone();
+        // 发送端由 self 持有, 不会被关闭
+        receiver.wait_for(Option::is_some).await.ok();
+    }
+
+    /// 等待直到关闭期限到达
+    pub(crate) async fn deadline(&self) {
+        let mut receiver = self.receiver.clone();
+        loop {
+            let Some(Some(deadline)) = *receiver.borrow_and_update() else {
+                receiver.changed().await.ok();
+                continue;
+            };
+            tokio::select! {
+                _ = sleep_until(deadline) => break,
+                // 期限可能被提前
+                _ = receiver.changed() => {}
+            }
+        }
+    }
+}
+
+/// 推送器关闭时未完成推送的部分
+#[derive(Debug)]
+pub struct ShutdownSummary<T> {
+    /// 已接收但尚未开始推送的消息
+    pub pending: Vec<T>,
+    /// 推送到一半被中断的消息
+    pub interrupted: Vec<InterruptedPush<T>>,
+}
+
+impl<T> Default for ShutdownSummary<T> {
+    fn default() -> Self {
+        Self {
+            pending: Vec::new(),
+            interrupted: Vec::new(),
+        }
+    }
+}
+
+impl<T> ShutdownSummary<T> {
+    /// 是否全部消息均已推送完成
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty() && self.interrupted.is_empty()
+    }
+}
+
+/// 推送被中断的消息
+///
+/// 被中断的消息不会发送到推送结果接收端
+#[derive(Debug)]
+pub struct InterruptedPush<T> {
+    /// 被推送的消息
+    pub data: T,
+    /// 中断前已经创建的推送批次
+    pub batches: Vec<BatchResult>,
+    /// 未发送的推送批次
+    pub unsent: Vec<UnsentBatch>,
+    /// 关闭时是否仍有订阅用户尚未获取, 这些用户不包含在 `unsent` 中
+    pub unfetched: bool,
+}
+
+/// 未发送的推送批次
+///
+/// ## Notify
+///
+/// 期限到达时仍在请求中的批次也被视为未发送, 但 Mob 可能已经接收了该批次
+#[derive(Debug, Clone)]
+pub struct UnsentBatch {
+    /// 推送目标
+    pub target: PushTarget,
+    /// 是否为透传消息批次
+    pub passthrough: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use tokio::time::{timeout, Instant};
+
+    use super::ShutdownSignal;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline() {
+        let signal = ShutdownSignal::new();
+        let handle = signal.handle();
+        assert!(!handle.is_shutdown());
+        assert!(timeout(Duration::from_secs(60), signal.triggered())
+            .await
+            .is_err());
+
+        let start = Instant::now();
+        handle.shutdown(Duration::from_secs(10));
+        signal.triggered().await;
+        assert!(handle.is_shutdown());
+
+        // 更晚的期限不生效
+        handle.shutdown(Duration::from_secs(20));
+        signal.deadline().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_without_deadline() {
+        let signal = ShutdownSignal::new();
+        let handle = signal.handle();
+        handle.shutdown(Duration::MAX);
+        signal.triggered().await;
+        // 没有期限时等待正在推送的消息全部完成
+        assert!(timeout(Duration::from_secs(3600), signal.deadline())
+            .await
+            .is_err());
+
+        let start = Instant::now();
+        handle.shutdown(Duration::from_secs(10));
+        signal.deadline().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+    }
+}
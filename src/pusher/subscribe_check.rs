@@ -0,0 +1,185 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+use typed_builder::TypedBuilder;
+
+use crate::{PushEntity, SubscribeFilter, UserMobId, UserSubscribeManage};
+
+/// 逐个用户检查订阅的方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubscribeCheckMode {
+    /// 通过 [`UserSubscribeManage::fetch_subscribe_filter`] 获取用户的筛选器,
+    /// 再由 [`SubscribeFilter::contains`] 判断
+    #[default]
+    Filter,
+    /// 通过 [`UserSubscribeManage::check_subscribed`] 判断
+    Subscribed,
+}
+
+/// 推送前逐个用户检查订阅的配置
+///
+/// 检查失败的用户将被跳过, 异常发送到异常接收端
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct SubscribeCheck {
+    /// 检查方式
+    #[builder(setter(!strip_option))]
+    pub(crate) mode: SubscribeCheckMode,
+    /// 同时检查的用户数量, 默认为 8
+    #[builder(default = 8, setter(!strip_option))]
+    pub(crate) concurrency: usize,
+    /// 检查结果的缓存时间, 未设置时不缓存
+    ///
+    /// [`SubscribeCheckMode::Filter`] 缓存用户的筛选器,
+    /// [`SubscribeCheckMode::Subscribed`] 缓存用户对数据源的订阅情况
+    pub(crate) cache_ttl: Option<Duration>,
+    /// 缓存的最大数量, 默认为 10000
+    #[builder(default = 10_000, setter(!strip_option))]
+    pub(crate) cache_capacity: usize,
+}
+
+impl Default for SubscribeCheck {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+type Resource<M> = <<M as UserSubscribeManage>::PushData as PushEntity>::Resource;
+
+/// 按照 [`SubscribeCheck`] 检查用户订阅
+pub(crate) struct SubscribeChecker<M: UserSubscribeManage> {
+    options: SubscribeCheck,
+    filters: TtlCache<String, Arc<M::Filter>>,
+    /// 以用户与数据源的哈希为键, 数据源可能无法跨线程移动
+    subscribed: TtlCache<(String, u64), bool>,
+}
+
+impl<M: UserSubscribeManage> SubscribeChecker<M> {
+    pub(crate) fn new(options: SubscribeCheck) -> Self {
+        Self {
+            filters: TtlCache::new(options.cache_ttl, options.cache_capacity),
+            subscribed: TtlCache::new(options.cache_ttl, options.cache_capacity),
+            options,
+        }
+    }
+
+    /// 同时检查的用户数量
+    pub(crate) fn concurrency(&self) -> usize {
+        self.options.concurrency.max(1)
+    }
+
+    /// 用户是否订阅了指定数据源
+    pub(crate) async fn is_subscribed(
+        &self,
+        manage: &M,
+        user: &M::UserIdentify,
+        resource: &Resource<M>,
+    ) -> Result<bool, M::Err> {
+        let key = user.get_mob_id().to_string();
+        match self.options.mode {
+            SubscribeCheckMode::Filter => {
+                let filter = match self.filters.get(&key) {
+                    Some(filter) => filter,
+                    None => {
+                        let filter = Arc::new(manage.fetch_subscribe_filter(user).await?);
+                        self.filters.insert(key, Arc::clone(&filter));
+                        filter
+                    }
+                };
+                Ok(filter.contains(resource)?)
+            }
+            SubscribeCheckMode::Subscribed => {
+                let mut hasher = DefaultHasher::new();
+                resource.hash(&mut hasher);
+                let key = (key, hasher.finish());
+                if let Some(subscribed) = self.subscribed.get(&key) {
+                    return Ok(subscribed);
+                }
+                let subscribed = manage.check_subscribed(user, resource).await?;
+                self.subscribed.insert(key, subscribed);
+                Ok(subscribed)
+            }
+        }
+    }
+}
+
+/// 带有过期时间与容量上限的缓存, 未设置过期时间时不缓存
+struct TtlCache<K, V> {
+    ttl: Option<Duration>,
+    capacity: usize,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> TtlCache<K, V> {
+    fn new(ttl: Option<Duration>, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, (Instant, V)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        let ttl = self.ttl?;
+        let entries = self.lock();
+        let (at, value) = entries.get(key)?;
+        (at.elapsed() < ttl).then(|| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let Some(ttl) = self.ttl else { return };
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.lock();
+        if entries.len() >= self.capacity {
+            entries.retain(|_, (at, _)| at.elapsed() < ttl);
+        }
+        // 仍然已满时淘汰最早缓存的一项
+        if entries.len() >= self.capacity {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (at, _))| *at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::TtlCache;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cache() {
+        let cache = TtlCache::new(Some(Duration::from_secs(10)), 2);
+        cache.insert("a", 1);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        // 超出容量时淘汰最早缓存的一项
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.get(&"b"), Some(2));
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(cache.get(&"c"), None);
+
+        let disabled = TtlCache::new(None, 2);
+        disabled.insert("a", 1);
+        assert_eq!(disabled.get(&"a"), None);
+    }
+}
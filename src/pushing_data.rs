@@ -1,8 +1,8 @@
 use std::{borrow::Cow, hash::Hash};
 
 use crate::{
-    push_notify::{android::AndroidNotify, ios::IosNotify},
-    PushForward,
+    push_notify::{android::AndroidNotify, ios::IosNotify, Extras},
+    MessageKind, Platforms, PushForward, PushTarget, Schedule,
 };
 
 /// the trait of Entity for Push
@@ -28,4 +28,52 @@ pub trait PushEntity: 'static + Sync + Send {
     fn ios_notify(&self, _notify: &mut IosNotify) {}
 
     fn push_forward(&self, _push_forward: &mut PushForward) {}
+
+    /// 获取当前推送消息附加的自定义键值对
+    fn extras(&self, _extras: &mut Extras) {}
+
+    /// 获取当前推送消息的推送目标
+    ///
+    /// 返回 `None` 时将通过 [`UserSubscribeManage::fetch_all_subscriber`](crate::UserSubscribeManage::fetch_all_subscriber)
+    /// 获取全部订阅用户, 并按 rid 分批推送
+    ///
+    /// 返回 `Some` 时直接向该目标推送, 不再获取订阅用户,
+    /// 例如全局公告可以使用 [`PushTarget::All`] 以单次请求广播
+    fn push_target(&self) -> Option<PushTarget> {
+        None
+    }
+
+    /// 获取当前推送消息的定时推送时间
+    ///
+    /// 返回 `None` 时立即推送
+    fn schedule(&self) -> Option<Schedule> {
+        None
+    }
+
+    /// 获取当前推送消息的消息类型
+    ///
+    /// 默认为通知消息
+    fn message_kind(&self) -> MessageKind {
+        MessageKind::Notify
+    }
+
+    /// 获取当前推送消息的推送平台
+    ///
+    /// 默认推送到 安卓 与 iOS,
+    /// 订阅用户通过 [`UserMobId::platform`](crate::UserMobId::platform) 提供平台时,
+    /// 不在推送平台中的用户将被跳过。
+    /// 推送平台为空时不会创建任何推送批次
+    fn platforms(&self) -> Platforms {
+        Platforms::default()
+    }
+
+    /// 获取当前推送消息的唯一标识
+    ///
+    /// 提供时, 每个推送批次携带由该标识与批次序号生成的固定 workno,
+    /// 重试或恢复同一批次时 workno 不变。
+    /// 推送器设置了 [去重窗口](crate::MobPusher::set_dedup_window) 时,
+    /// 窗口期内相同标识的消息不会被重复推送
+    fn identity(&self) -> Option<Cow<'_, str>> {
+        None
+    }
 }
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, TimeZone, Utc};
+
+/// 免打扰时段所在的时区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietZone {
+    /// 固定的 UTC 偏移, 不随夏令时变化
+    Fixed(FixedOffset),
+    /// IANA 时区, 按照时区规则计算夏令时
+    #[cfg(feature = "chrono-tz")]
+    Named(chrono_tz::Tz),
+}
+
+impl From<FixedOffset> for QuietZone {
+    fn from(offset: FixedOffset) -> Self {
+        Self::Fixed(offset)
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl From<chrono_tz::Tz> for QuietZone {
+    fn from(tz: chrono_tz::Tz) -> Self {
+        Self::Named(tz)
+    }
+}
+
+/// 用户的免打扰时段
+///
+/// 时段按照用户所在时区的本地时间计算, `start` 晚于 `end` 时跨越午夜,
+/// 如 22:00 至次日 08:00
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    zone: QuietZone,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    /// 创建免打扰时段, `start` 与 `end` 相同时视为没有免打扰时段
+    ///
+    /// 用户所在地区使用夏令时时, 启用 `chrono-tz` feature 并传入 `chrono_tz::Tz`
+    pub fn new(zone: impl Into<QuietZone>, start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            zone: zone.into(),
+            start,
+            end,
+        }
+    }
+
+    /// 用户所在时区
+    pub fn zone(&self) -> QuietZone {
+        self.zone
+    }
+
+    /// 指定时间所在的免打扰时段的结束时间, 不在免打扰时段内时为 `None`
+    pub fn end_after<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> Option<DateTime<FixedOffset>> {
+        match &self.zone {
+            QuietZone::Fixed(offset) => self.end_in(offset, time),
+            #[cfg(feature = "chrono-tz")]
+            QuietZone::Named(tz) => self.end_in(tz, time),
+        }
+    }
+
+    fn end_in<Z: TimeZone, Tz: TimeZone>(
+        &self,
+        zone: &Z,
+        time: &DateTime<Tz>,
+    ) -> Option<DateTime<FixedOffset>> {
+        let local = time.with_timezone(zone);
+        let (date, now) = (local.date_naive(), local.time());
+        let end_date = if self.start < self.end {
+            (self.start <= now && now < self.end).then_some(date)?
+        } else if self.start > self.end {
+            if now >= self.start {
+                date + Duration::days(1)
+            } else if now < self.end {
+                date
+            } else {
+                return None;
+            }
+        } else {
+            return None;
+        };
+        let end = end_date.and_time(self.end);
+        // 夏令时切换时, 重复的本地时间取较早者, 跳过的本地时间顺延一小时
+        zone.from_local_datetime(&end)
+            .earliest()
+            .or_else(|| {
+                zone.from_local_datetime(&(end + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|end| end.fixed_offset())
+    }
+
+    /// 当前是否处于免打扰时段
+    pub fn is_quiet_now(&self) -> bool {
+        self.end_after(&Utc::now()).is_some()
+    }
+}
+
+/// 推送器对处于免打扰时段的用户的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietHoursMode {
+    /// 定时到免打扰时段结束时推送, 无法定时时推送静默通知
+    Defer,
+    /// 立即推送静默通知, 不播放提示音与震动
+    Silent,
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{FixedOffset, NaiveTime, TimeZone};
+
+    use super::QuietHours;
+
+    #[test]
+    fn test_end_after() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let at = |d, h| offset.with_ymd_and_hms(2023, 3, d, h, 0, 0).unwrap();
+
+        // 跨越午夜
+        let night = QuietHours::new(offset, time(22, 0), time(8, 0));
+        assert_eq!(night.end_after(&at(1, 23)), Some(at(2, 8)));
+        assert_eq!(night.end_after(&at(2, 3)), Some(at(2, 8)));
+        assert_eq!(night.end_after(&at(2, 8)), None);
+        assert_eq!(night.end_after(&at(2, 12)), None);
+        // 其他时区的时间按照用户时区计算
+        let utc = at(2, 3).with_timezone(&chrono::Utc);
+        assert_eq!(night.end_after(&utc), Some(at(2, 8)));
+
+        let noon = QuietHours::new(offset, time(12, 0), time(14, 30));
+        assert_eq!(
+            noon.end_after(&at(1, 13)),
+            Some(offset.with_ymd_and_hms(2023, 3, 1, 14, 30, 0).unwrap())
+        );
+        assert_eq!(noon.end_after(&at(1, 15)), None);
+
+        let empty = QuietHours::new(offset, time(12, 0), time(12, 0));
+        assert_eq!(empty.end_after(&at(1, 12)), None);
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn test_dst() {
+        use chrono::Utc;
+        use chrono_tz::America::New_York;
+
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let utc = |d, h, m| Utc.with_ymd_and_hms(2023, 3, d, h, m, 0).unwrap();
+
+        // 2023-03-12 02:00 起使用夏令时 (UTC-4)
+        let night = QuietHours::new(New_York, time(22, 0), time(8, 0));
+        let before = New_York.with_ymd_and_hms(2023, 3, 11, 23, 0, 0).unwrap();
+        assert_eq!(
+            night.end_after(&before),
+            Some(utc(12, 12, 0).fixed_offset())
+        );
+
+        // 结束时间在切换时被跳过
+        let skipped = QuietHours::new(New_York, time(1, 0), time(2, 30));
+        let at = New_York.with_ymd_and_hms(2023, 3, 12, 1, 30, 0).unwrap();
+        assert_eq!(skipped.end_after(&at), Some(utc(12, 7, 30).fixed_offset()));
+    }
+}
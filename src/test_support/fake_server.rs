@@ -0,0 +1,237 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+
+use super::MockResponse;
+use crate::{api::sign, MobPushConfig};
+
+/// 模拟服务支持的接口, 除 `createPush` 外均返回空的成功响应
+const KNOWN_PATHS: &[&str] = &[
+    "/v3/push/createPush",
+    "/v3/push/getByBatchId",
+    "/v3/push/getByWorkno",
+    "/v3/push/listTimedTask",
+    "/v3/push/cancel",
+    "/v3/push/drop",
+    "/v3/push/replace",
+    "/v3/stats/getByBatchId",
+    "/v3/stats/getByWorkno",
+    "/device-v3/updateTags",
+    "/device-v3/updateAlias",
+    "/device-v3/getById",
+    "/device-v3/queryByTags",
+];
+
+/// 签名校验失败时的响应状态
+pub const SIGN_INVALID_STATE: u16 = 401;
+
+/// 模拟服务收到的请求
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// 请求路径, 如 `/v3/push/createPush`
+    pub path: String,
+    /// `key` 请求头
+    pub key: Option<String>,
+    /// `sign` 请求头与 `key` 请求头是否均校验通过
+    pub sign_valid: bool,
+    /// 请求体, 无法解析为 JSON 时为 `Value::Null`
+    pub body: Value,
+}
+
+#[derive(Debug, Default)]
+struct ServerState {
+    requests: Vec<RecordedRequest>,
+    scripts: HashMap<String, VecDeque<MockResponse>>,
+    batch_seq: u64,
+}
+
+/// 进程内的 Mob Push 模拟服务
+///
+/// 需要在 tokio 运行时中启动, 被丢弃时服务停止
+pub struct FakeMobServer {
+    addr: SocketAddr,
+    config: MobPushConfig,
+    state: Arc<Mutex<ServerState>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeMobServer {
+    /// 使用测试用的 app key 与 app secret 启动模拟服务
+    pub async fn start() -> std::io::Result<Self> {
+        Self::start_with_config(MobPushConfig::new("fake-key", "fake-secret")).await
+    }
+
+    /// 使用给定配置中的 app key 与 app secret 启动模拟服务
+    pub async fn start_with_config(mut config: MobPushConfig) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        config.base_url = format!("http://{addr}")
+            .parse()
+            .expect("Invalid Fake Server Url");
+
+        let state = Arc::new(Mutex::new(ServerState::default()));
+        let service = {
+            let state = Arc::clone(&state);
+            let config = config.clone();
+            make_service_fn(move |_| {
+                let state = Arc::clone(&state);
+                let config = config.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req| {
+                        handle(Arc::clone(&state), config.clone(), req)
+                    }))
+                }
+            })
+        };
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let server = Server::from_tcp(listener)
+            .map_err(std::io::Error::other)?
+            .serve(service)
+            .with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+            });
+        tokio::spawn(server);
+
+        Ok(Self {
+            addr,
+            config,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// 模拟服务的监听地址
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 指向模拟服务的配置, 可用于 [`MobPusher::new`](crate::MobPusher::new)
+    pub fn config(&self) -> MobPushConfig {
+        self.config.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ServerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 为接口预设一个响应, 预设响应用尽后恢复正常响应
+    ///
+    /// [`MockResponse::Transport`] 使服务直接断开连接
+    pub fn script(&self, path: &str, response: MockResponse) -> &Self {
+        self.lock()
+            .scripts
+            .entry(path.to_owned())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// 收到的全部请求
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// 指定接口收到的请求
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|req| req.path == path)
+            .cloned()
+            .collect()
+    }
+
+    /// `createPush` 接口收到的请求体
+    pub fn pushes(&self) -> Vec<Value> {
+        self.requests_to("/v3/push/createPush")
+            .into_iter()
+            .map(|req| req.body)
+            .collect()
+    }
+}
+
+impl Drop for FakeMobServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+fn respond(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    let mut resp = Response::new(body.into());
+    *resp.status_mut() = status;
+    resp
+}
+
+fn respond_json(body: Value) -> Response<Body> {
+    respond(StatusCode::OK, body.to_string())
+}
+
+async fn handle(
+    state: Arc<Mutex<ServerState>>,
+    config: MobPushConfig,
+    req: Request<Body>,
+) -> std::io::Result<Response<Body>> {
+    let path = req.uri().path().to_owned();
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+    };
+    let key = header("key");
+    let req_sign = header("sign");
+    let payload = hyper::body::to_bytes(req.into_body())
+        .await
+        .unwrap_or_default();
+
+    let sign_valid = key.as_deref() == Some(config.key.as_str())
+        && req_sign.as_deref() == Some(sign(&payload, &config.secret).as_str());
+    let body = serde_json::from_slice(&payload).unwrap_or(Value::Null);
+
+    let (script, batch_seq) = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.requests.push(RecordedRequest {
+            path: path.clone(),
+            key,
+            sign_valid,
+            body,
+        });
+        state.batch_seq += 1;
+        let script = state.scripts.get_mut(&path).and_then(VecDeque::pop_front);
+        (script, state.batch_seq)
+    };
+
+    if let Some(script) = script {
+        let is_push = path == "/v3/push/createPush";
+        let (status, body) = script
+            .resolve(is_push)
+            .await
+            .map_err(std::io::Error::other)?;
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return Ok(respond(status, body));
+    }
+
+    let resp = if !sign_valid {
+        json!({"status": SIGN_INVALID_STATE, "error": "sign verify failed"})
+    } else if path == "/v3/push/createPush" {
+        json!({"status": 200, "res": {"batchId": format!("fake-batch-{batch_seq}")}})
+    } else if KNOWN_PATHS.contains(&path.as_str()) {
+        json!({"status": 200, "res": null})
+    } else {
+        return Ok(respond(StatusCode::NOT_FOUND, "Not Found"));
+    };
+    Ok(respond_json(resp))
+}
@@ -0,0 +1,18 @@
+//! 离线测试支持, 需要启用 `test-support` feature
+//!
+//! - [`FakeMobServer`] 是一个进程内的 Mob Push 模拟服务,
+//!   校验请求签名、记录收到的请求, 并支持预设异常响应,
+//!   配合 [`FakeMobServer::config`] 即可在无网络环境下测试 [`MobPusher`](crate::MobPusher)
+//! - [`RecordingClient`] 是一个不发起任何网络请求的 [`PushClient`](crate::http_client::PushClient),
+//!   记录推送器构造的全部请求, 并按照预设返回响应
+
+mod fake_server;
+mod recording;
+
+pub use self::{
+    fake_server::{FakeMobServer, RecordedRequest, SIGN_INVALID_STATE},
+    recording::{
+        MockResponse, RecordedCall, RecordingClient, RecordingError, RecordingRequest,
+        RecordingRequestBuilder, RecordingResponse,
+    },
+};
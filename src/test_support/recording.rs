@@ -0,0 +1,324 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::{
+    api::sign,
+    http_client::{PushClient, PushRequestBuilder, PushResponse},
+    MobPushConfig,
+};
+
+/// [`RecordingClient`] 与 [`FakeMobServer`](super::FakeMobServer) 预设的响应
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// 成功响应, `createPush` 使用给定的推送批次 ID
+    Success { batch_id: String },
+    /// 返回 Mob 异常状态
+    Mob { state: u16, msg: String },
+    /// 返回指定的 HTTP 状态与响应体
+    Http { status: u16, body: String },
+    /// 发送请求失败
+    Transport(String),
+    /// 延迟指定时长后返回响应
+    Delay(Duration, Box<MockResponse>),
+}
+
+impl MockResponse {
+    /// 成功响应
+    pub fn new_success(batch_id: impl Into<String>) -> Self {
+        Self::Success {
+            batch_id: batch_id.into(),
+        }
+    }
+
+    /// Mob 异常响应
+    pub fn new_mob(state: u16, msg: impl Into<String>) -> Self {
+        Self::Mob {
+            state,
+            msg: msg.into(),
+        }
+    }
+
+    /// Mob 限流响应
+    pub fn new_rate_limited() -> Self {
+        Self::new_mob(429, "rate limited")
+    }
+
+    /// Mob 服务端异常响应
+    pub fn new_server_error() -> Self {
+        Self::new_mob(500, "server error")
+    }
+
+    /// 延迟后返回当前响应
+    pub fn delayed(self, delay: Duration) -> Self {
+        Self::Delay(delay, Box::new(self))
+    }
+
+    /// 等待预设的延迟后返回响应状态与响应体, 请求发送失败时返回异常信息
+    pub(crate) async fn resolve(mut self, is_push: bool) -> Result<(u16, String), String> {
+        loop {
+            let (status, body) = match self {
+                MockResponse::Delay(delay, inner) => {
+                    tokio::time::sleep(delay).await;
+                    self = *inner;
+                    continue;
+                }
+                MockResponse::Transport(msg) => return Err(msg),
+                MockResponse::Success { batch_id } if is_push => (
+                    200,
+                    json!({"status": 200, "res": {"batchId": batch_id}}).to_string(),
+                ),
+                MockResponse::Success { .. } => (200, json!({"status": 200}).to_string()),
+                MockResponse::Mob { state, msg } => {
+                    (200, json!({"status": state, "error": msg}).to_string())
+                }
+                MockResponse::Http { status, body } => (status, body),
+            };
+            break Ok((status, body));
+        }
+    }
+}
+
+/// [`RecordingClient`] 模拟的请求发送失败
+#[derive(Debug, Clone)]
+pub struct RecordingError(pub String);
+
+impl Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Recording Client Error : {}", self.0)
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+/// [`RecordingClient`] 记录的请求
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    /// 请求地址
+    pub url: url::Url,
+    /// 请求头
+    pub headers: Vec<(&'static str, String)>,
+    /// 请求体, 无法解析为 JSON 时为 `Value::Null`
+    pub body: Value,
+    payload: Vec<u8>,
+}
+
+impl RecordedCall {
+    /// 请求路径, 如 `/v3/push/createPush`
+    pub fn path(&self) -> &str {
+        self.url.path()
+    }
+
+    /// 获取请求头
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 请求是否按照给定配置正确签名
+    pub fn is_signed_by(&self, config: &MobPushConfig) -> bool {
+        self.header("key") == Some(config.key.as_str())
+            && self.header("sign") == Some(sign(&self.payload, &config.secret).as_str())
+    }
+}
+
+#[derive(Debug, Default)]
+struct RecordingState {
+    calls: Vec<RecordedCall>,
+    script: VecDeque<MockResponse>,
+    batch_seq: u64,
+}
+
+/// 不发起任何网络请求的 [`PushClient`]
+///
+/// 记录全部请求, 并按照预设顺序返回响应, 预设响应用尽后返回成功响应。
+/// 可克隆持有, 克隆的客户端共享同一份记录
+///
+/// 配合 [`RecordingClient::config`] 使用时无需任何配置文件
+#[derive(Debug, Clone, Default)]
+pub struct RecordingClient {
+    state: Arc<Mutex<RecordingState>>,
+}
+
+impl RecordingClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 测试用的配置, 可用于 [`MobPusher::new`](crate::MobPusher::new)
+    pub fn config() -> MobPushConfig {
+        MobPushConfig::new("recording-key", "recording-secret")
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecordingState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 预设下一个尚未预设的请求的响应
+    pub fn script(&self, response: MockResponse) -> &Self {
+        self.lock().script.push_back(response);
+        self
+    }
+
+    /// 记录的全部请求
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.lock().calls.clone()
+    }
+
+    /// 发送到指定接口的请求
+    pub fn calls_to(&self, path: &str) -> Vec<RecordedCall> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.path() == path)
+            .cloned()
+            .collect()
+    }
+
+    /// `createPush` 请求的请求体
+    pub fn pushes(&self) -> Vec<Value> {
+        self.calls_to("/v3/push/createPush")
+            .into_iter()
+            .map(|call| call.body)
+            .collect()
+    }
+
+    /// 清空记录的请求与预设的响应
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.calls.clear();
+        state.script.clear();
+    }
+
+    /// 断言 `createPush` 请求的数量
+    #[track_caller]
+    pub fn assert_push_count(&self, count: usize) {
+        let pushes = self.pushes();
+        assert_eq!(
+            pushes.len(),
+            count,
+            "expect {count} createPush requests, found {}: {pushes:#?}",
+            pushes.len()
+        );
+    }
+
+    /// 断言全部请求均按照给定配置正确签名
+    #[track_caller]
+    pub fn assert_all_signed(&self, config: &MobPushConfig) {
+        for call in self.calls() {
+            assert!(
+                call.is_signed_by(config),
+                "request to {} is not signed by app key {}",
+                call.url,
+                config.key
+            );
+        }
+    }
+
+    fn respond(&self, req: RecordingRequest) -> MockResponse {
+        let body = serde_json::from_slice(&req.body).unwrap_or(Value::Null);
+        let mut state = self.lock();
+        let is_push = req.url.path() == "/v3/push/createPush";
+        state.calls.push(RecordedCall {
+            url: req.url,
+            headers: req.headers,
+            body,
+            payload: req.body,
+        });
+        state.batch_seq += 1;
+        let batch_seq = state.batch_seq;
+
+        state.script.pop_front().unwrap_or_else(|| {
+            if is_push {
+                MockResponse::new_success(format!("recorded-batch-{batch_seq}"))
+            } else {
+                MockResponse::new_success("")
+            }
+        })
+    }
+}
+
+/// [`RecordingClient`] 构造的请求
+#[derive(Debug, Clone)]
+pub struct RecordingRequest {
+    url: url::Url,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+pub struct RecordingRequestBuilder(RecordingRequest);
+
+/// [`RecordingClient`] 返回的响应
+#[derive(Debug, Clone)]
+pub struct RecordingResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+#[async_trait]
+impl PushClient for RecordingClient {
+    type RequestBuilder = RecordingRequestBuilder;
+    type Error = RecordingError;
+
+    fn post(&self, url: impl Into<url::Url>) -> Self::RequestBuilder {
+        RecordingRequestBuilder(RecordingRequest {
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        })
+    }
+
+    async fn send_request(&self, req: RecordingRequest) -> Result<RecordingResponse, Self::Error> {
+        let is_push = req.url.path() == "/v3/push/createPush";
+        let (status, body) = self
+            .respond(req)
+            .resolve(is_push)
+            .await
+            .map_err(RecordingError)?;
+        Ok(RecordingResponse {
+            status,
+            body: body.into_bytes(),
+        })
+    }
+}
+
+impl PushRequestBuilder for RecordingRequestBuilder {
+    type Error = RecordingError;
+    type Request = RecordingRequest;
+    type Response = RecordingResponse;
+
+    fn header(mut self, key: &'static str, value: &str) -> Self {
+        self.0.headers.push((key, value.to_owned()));
+        self
+    }
+
+    fn body(mut self, payload: Vec<u8>) -> Self {
+        self.0.body = payload;
+        self
+    }
+
+    fn build(self) -> Result<Self::Request, Self::Error> {
+        Ok(self.0)
+    }
+}
+
+#[async_trait]
+impl PushResponse for RecordingResponse {
+    type Error = RecordingError;
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    async fn bytes(self) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.body)
+    }
+}
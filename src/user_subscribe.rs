@@ -1,12 +1,13 @@
-use crate::PushEntity;
+use crate::{Platform, PushEntity, QuietHours};
 use async_trait::async_trait;
+use futures_util::{stream::BoxStream, StreamExt};
 
 /// 用户消息订阅管理器, 负责管理mob push 用户订阅的持久化数据获取
 #[async_trait]
 pub trait UserSubscribeManage: 'static + Sync + Send {
     type UserIdentify: UserMobId;
     type PushData: PushEntity;
-    type Filter: SubscribeFilter;
+    type Filter: SubscribeFilter<Data = Self::PushData>;
     type Err: From<<Self::Filter as SubscribeFilter>::Err>
         + std::error::Error
         + Send
@@ -31,6 +32,18 @@ pub trait UserSubscribeManage: 'static + Sync + Send {
         &self,
         data_resource: &<Self::PushData as PushEntity>::Resource,
     ) -> Result<Vec<Self::UserIdentify>, Self::Err>;
+
+    /// 分页获取全部订阅了指定数据源的用户
+    ///
+    /// 推送器每凑满一批用户即推送, 无需一次加载全部用户。
+    /// 默认通过 [`fetch_all_subscriber`](Self::fetch_all_subscriber) 一次获取全部用户,
+    /// 订阅用户较多时建议覆盖该方法
+    fn fetch_subscriber_pages<'a>(
+        &'a self,
+        data_resource: &'a <Self::PushData as PushEntity>::Resource,
+    ) -> BoxStream<'a, Result<Vec<Self::UserIdentify>, Self::Err>> {
+        futures_util::stream::once(self.fetch_all_subscriber(data_resource)).boxed()
+    }
 }
 
 /// 订阅用户筛选器
@@ -51,4 +64,19 @@ pub trait UserMobId: Sized + Send + Sync + 'static {
     type MobId: ToString + 'static + Send + Sync + Sized;
     /// 用户推送用mob ID
     fn get_mob_id(&self) -> Self::MobId;
+
+    /// 用户设备所属的平台
+    ///
+    /// 提供平台后, 推送器将按平台分组推送, 使仅安卓支持的配置只发往安卓设备
+    fn platform(&self) -> Option<Platform> {
+        None
+    }
+
+    /// 用户的免打扰时段
+    ///
+    /// 推送器通过 [`MobPusher::set_quiet_hours`](crate::MobPusher::set_quiet_hours)
+    /// 启用后, 处于免打扰时段的用户将延后推送或收到静默通知
+    fn quiet_hours(&self) -> Option<QuietHours> {
+        None
+    }
 }
@@ -1,92 +1,37 @@
 use std::{
     convert::Infallible,
     fmt::Debug,
+    future::Future,
     marker::{Send, Sync},
     time::Duration,
 };
 
+use futures_util::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
 use mob_push::{
     self,
-    http_client::{PushClient, PushRequestBuilder, PushResponse},
-    load_config_from_default,
+    http_client::{ClientOptions, HyperClient, PushClient, ReqwestClient, TlsBackend},
     push_notify::{
-        android::{
-            notify_style::CustomStyle, sound::WarnSound, AndroidNotify, Badge, Image, NotifyStyle,
-        },
+        android::{sound::WarnSound, AndroidNotify, Badge, Image, NotifyStyle},
         ios::{IosBadgeType, IosNotify, IosPushSound, IosRichTextType},
     },
-    MobPusher, PushEntity, SubscribeFilter, UserMobId, UserSubscribeManage,
+    test_support::{FakeMobServer, MockResponse, RecordingClient},
+    MobPushConfig, MobPushError, MobPusher, Outbox, OutboxEntry, OutboxId, PlannedBatch,
+    PlannedDelivery, Platforms, PushEntity, PushTarget, QuietHours, QuietHoursMode, RateLimit,
+    SubscribeCheck, SubscribeFilter, UserMobId, UserSubscribeManage,
 };
-use tokio::time;
-
-struct Client(reqwest::Client);
-struct RequestBuilder(reqwest::RequestBuilder);
-struct Response(reqwest::Response);
-
-impl PushClient for Client {
-    type RequestBuilder = RequestBuilder;
-
-    type Error = reqwest::Error;
-
-    fn post(&self, url: impl Into<url::Url>) -> Self::RequestBuilder {
-        RequestBuilder(self.0.post(url.into()))
-    }
-
-    fn send_request<'life0,'async_trait>(&'life0 self,req: <Self::RequestBuilder as mob_push::http_client::PushRequestBuilder> ::Request,) ->  core::pin::Pin<Box<dyn core::future::Future<Output = Result< <Self::RequestBuilder as mob_push::http_client::PushRequestBuilder> ::Response,Self::Error> > + core::marker::Send+'async_trait> >where 'life0:'async_trait,Self:'async_trait{
-        Box::pin(async {
-            let resp = self.0.execute(req).await?;
-            Ok(Response(resp))
-        })
-    }
-}
-
-impl PushRequestBuilder for RequestBuilder {
-    type Error = reqwest::Error;
+use serde_json::json;
 
-    type Request = reqwest::Request;
-
-    type Response = Response;
-
-    fn header(self, key: &'static str, value: &str) -> Self {
-        Self(self.0.header(key, value))
-    }
-
-    fn body(self, payload: Vec<u8>) -> Self {
-        Self(self.0.body(payload))
-    }
-
-    fn build(self) -> Result<Self::Request, Self::Error> {
-        self.0.build()
-    }
-}
-
-impl PushResponse for Response {
-    type Error = reqwest::Error;
-
-    fn status(&self) -> u16 {
-        self.0.status().as_u16()
-    }
-
-    fn bytes<'async_trait>(
-        self,
-    ) -> core::pin::Pin<
-        Box<
-            dyn core::future::Future<Output = Result<Vec<u8>, Self::Error>>
-                + core::marker::Send
-                + 'async_trait,
-        >,
-    >
-    where
-        Self: 'async_trait,
-    {
-        Box::pin(async { self.0.bytes().await.map(Into::into) })
-    }
-}
+type AndroidSetter = Box<dyn Fn(&mut AndroidNotify) -> &mut AndroidNotify + Sync + Send + 'static>;
+type IosSetter = Box<dyn Fn(&mut IosNotify) -> &mut IosNotify + Sync + Send + 'static>;
 
 #[derive(Default)]
 struct TestMsg {
-    android: Option<Box<dyn Fn(&mut AndroidNotify) -> &mut AndroidNotify + Sync + Send + 'static>>,
-    ios: Option<Box<dyn Fn(&mut IosNotify) -> &mut IosNotify + Sync + Send + 'static>>,
+    android: Option<AndroidSetter>,
+    ios: Option<IosSetter>,
+    identity: Option<&'static str>,
 }
 
 impl Debug for TestMsg {
@@ -94,6 +39,7 @@ impl Debug for TestMsg {
         f.debug_struct("TestMsg")
             .field("android", &self.android.is_some())
             .field("ios", &self.ios.is_some())
+            .field("identity", &self.identity)
             .finish()
     }
 }
@@ -106,7 +52,7 @@ impl TestMsg {
         let android = Box::new(android);
         TestMsg {
             android: Some(android),
-            ios: self.ios,
+            ..self
         }
     }
 
@@ -116,8 +62,8 @@ impl TestMsg {
     {
         let ios = Box::new(ios);
         TestMsg {
-            android: self.android,
             ios: Some(ios),
+            ..self
         }
     }
 }
@@ -150,10 +96,15 @@ impl PushEntity for TestMsg {
             ios_notify(notify);
         }
     }
+
+    fn identity(&self) -> Option<std::borrow::Cow<'_, str>> {
+        self.identity.map(Into::into)
+    }
 }
 
 struct User {
     mob_id: String,
+    quiet_hours: Option<QuietHours>,
 }
 
 impl UserMobId for User {
@@ -162,6 +113,10 @@ impl UserMobId for User {
     fn get_mob_id(&self) -> Self::MobId {
         self.mob_id.clone()
     }
+
+    fn quiet_hours(&self) -> Option<QuietHours> {
+        self.quiet_hours
+    }
 }
 
 struct Filter;
@@ -209,8 +164,7 @@ impl UserSubscribeManage for Manage {
         _user_id: &Self::UserIdentify,
         data_resource: &<Self::PushData as PushEntity>::Resource,
     ) -> Result<bool, Self::Err> {
-        let resp = Filter.contains(data_resource);
-        resp
+        Filter.contains(data_resource)
     }
 
     async fn fetch_all_subscriber(
@@ -227,6 +181,7 @@ impl UserSubscribeManage for Manage {
                 // },
                 User {
                     mob_id: "65l05lvwtep0fls".into(),
+                    quiet_hours: None,
                 },
             ]
         } else {
@@ -237,61 +192,164 @@ impl UserSubscribeManage for Manage {
     }
 }
 
-fn test_pushing<F>(msg: F)
-where
-    F: FnOnce() -> TestMsg,
-{
-    let client = reqwest::Client::new();
-    load_config_from_default();
-    let (mob_push, sender, mut err_rx) = MobPusher::new(Client(client), Manage, 8);
+/// 推送多个批次的测试中不等待速率限制
+const FAST: RateLimit = RateLimit {
+    qps: 100.0,
+    burst: 10,
+};
 
-    let rt = tokio::runtime::Builder::new_current_thread()
+/// 在单线程运行时中执行测试
+fn block_on<F: Future>(fut: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
-        .expect("Rt start Error");
+        .expect("Rt start Error")
+        .block_on(fut)
+}
 
-    rt.block_on(async move {
-        let handle = tokio::spawn(mob_push.start_up());
+/// 启动推送器并依次发送全部消息, 等待推送器处理完全部消息后退出
+///
+/// `setup` 在启动前配置推送器, 其返回值与推送期间的全部异常一并返回
+async fn run_pushing<M, C, S, R>(
+    client: C,
+    manage: M,
+    config: MobPushConfig,
+    msgs: Vec<TestMsg>,
+    setup: S,
+) -> (R, Vec<MobPushError<M, C>>)
+where
+    M: UserSubscribeManage<PushData = TestMsg>,
+    C: PushClient,
+    C::Error: std::error::Error,
+    S: FnOnce(&mut MobPusher<M, C>) -> R,
+{
+    let (mut mob_push, sender, mut err_rx) = MobPusher::new(client, manage, config, 8);
+    let setup = setup(&mut mob_push);
+    let sending = async move {
+        for msg in msgs {
+            sender.send(msg).await.unwrap();
+        }
+    };
 
-        sender.send(msg()).await.unwrap();
+    // 推送期间持续读取异常
+    let receiving = async {
+        let mut errors = Vec::new();
+        while let Some(err) = err_rx.recv().await {
+            errors.push(err);
+        }
+        errors
+    };
 
-        time::sleep(Duration::from_secs(1)).await;
+    let pushing = async { tokio::join!(mob_push.start_up(), sending, receiving) };
+    let (summary, (), errors) = tokio::time::timeout(Duration::from_secs(30), pushing)
+        .await
+        .expect("pusher stalled");
+    assert!(summary.is_empty());
 
-        drop(sender);
+    (setup, errors)
+}
 
-        let err = time::timeout(Duration::from_millis(500), err_rx.recv()).await;
-        if let Ok(err) = err {
-            println!("{err:?}")
+/// 向模拟服务推送一条消息, `script` 依次作用于推送请求
+fn push_to_server<F, C>(msg: F, client: C, script: &[MockResponse]) -> FakeMobServer
+where
+    F: FnOnce() -> TestMsg,
+    C: PushClient,
+    C::Error: std::error::Error,
+{
+    block_on(async move {
+        let server = FakeMobServer::start().await.expect("Fake Server Start");
+        for resp in script {
+            server.script("/v3/push/createPush", resp.clone());
         }
 
-        handle.abort();
+        let ((), errors) = run_pushing(client, Manage, server.config(), vec![msg()], |_| ()).await;
+        assert!(errors.is_empty(), "{errors:?}");
+        server
     })
 }
 
+/// 推送一条消息, 返回推送请求中的 `pushNotify`
+fn test_pushing<F>(msg: F) -> serde_json::Value
+where
+    F: FnOnce() -> TestMsg,
+{
+    let server = push_to_server(msg, ReqwestClient::new().unwrap(), &[]);
+    let requests = server.requests_to("/v3/push/createPush");
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].sign_valid);
+
+    let body = &requests[0].body;
+    assert_eq!(body["pushTarget"]["rids"], json!(["65l05lvwtep0fls"]));
+    assert_eq!(body["pushNotify"]["title"], "新饼来袭");
+    assert_eq!(body["pushNotify"]["plats"], json!([1, 2]));
+    body["pushNotify"].clone()
+}
+
+#[test]
+fn test_retry() {
+    let server = push_to_server(
+        TestMsg::default,
+        ReqwestClient::new().unwrap(),
+        &[
+            MockResponse::new_server_error(),
+            MockResponse::new_rate_limited(),
+        ],
+    );
+    let pushes = server.pushes();
+    assert_eq!(pushes.len(), 3);
+    assert!(pushes.iter().all(|push| push == &pushes[0]));
+}
+
+#[test]
+fn test_hyper_client() {
+    let options = ClientOptions::builder()
+        .request_timeout(Duration::from_secs(5))
+        .pool_max_idle_per_host(1)
+        .tls(TlsBackend::Rustls)
+        .build();
+    let server = push_to_server(
+        TestMsg::default,
+        HyperClient::new_with_options(options),
+        &[MockResponse::Transport("connection reset".into())],
+    );
+    // 断开连接后重试
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|req| req.sign_valid));
+}
+
 #[test]
 fn test_push() {
-    test_pushing(|| TestMsg::default());
+    let notify = test_pushing(TestMsg::default);
+    assert_eq!(notify["type"], 1);
+    assert!(notify.get("androidNotify").is_none());
+    assert!(notify.get("iosNotify").is_none());
 }
 
 /// 角标数值没啥意义
 /// 每次推送都是+1
 #[test]
 fn test_android_badge() {
-    test_pushing(|| TestMsg::default().set_android(|an| an.set_badge(Badge::Add(1))));
+    let notify = test_pushing(|| TestMsg::default().set_android(|an| an.set_badge(Badge::Add(1))));
+    assert_eq!(
+        notify["androidNotify"],
+        json!({"androidBadge": 1, "androidBadgeType": 2})
+    );
 }
 
 /// 小米似乎只有一种声音？
 #[test]
 fn test_android_warn() {
-    test_pushing(|| {
+    let notify = test_pushing(|| {
         TestMsg::default().set_android(|an| an.set_warn(WarnSound::Vibration & WarnSound::Prompt))
     });
+    assert_eq!(notify["androidNotify"], json!({"warn": "21"}));
 }
 
 /// 使用image 推送可行
 #[test]
 fn test_icon() {
-    test_pushing(|| {
+    let notify = test_pushing(|| {
         TestMsg::default().set_android(|an| {
             let _img = "https://static.mob.com/www_mob_com/.nuxt/dist/client/img/62893e6.png";
             let icon = "https://www.mob.com/favicon.ico";
@@ -299,6 +357,10 @@ fn test_icon() {
             an.set_image(Image::new_image(icon))
         })
     });
+    assert_eq!(
+        notify["androidNotify"],
+        json!({"image": "https://www.mob.com/favicon.ico"})
+    );
 }
 
 /// - 长内容1
@@ -318,7 +380,7 @@ fn test_icon() {
 ///
 #[test]
 fn test_style() {
-    test_pushing(|| {
+    let notify = test_pushing(|| {
         TestMsg::default().set_android(|an| {
             let url = "https://i2.hdslb.com/bfs/archive/355b2e7886f337ff3d0951a057f0022be527f309.jpg@672w_378h_1c";
             an
@@ -330,11 +392,16 @@ fn test_style() {
                 .set_notify_style(NotifyStyle::new_big_vision(url))
         })
     });
+    assert_eq!(notify["androidNotify"]["style"], 2);
+    assert_eq!(
+        notify["androidNotify"]["content"].as_array().unwrap().len(),
+        1
+    );
 }
 
 #[test]
 fn test_android_notify_push() {
-    test_pushing(|| {
+    let notify = test_pushing(|| {
         TestMsg::default().set_android(|an|{
             an
             .set_notify_style(NotifyStyle::new_big_vision("https://i0.hdslb.com/bfs/archive/94bdaa89d9e1775f04bdfb705512a61e5de70628.jpg@672w_378h_1c"))
@@ -343,28 +410,514 @@ fn test_android_notify_push() {
      .set_warn(WarnSound::Prompt & WarnSound::IndicatorLight & WarnSound::Vibration)
 
     })
-    })
+    });
+    let android = &notify["androidNotify"];
+    assert_eq!(android["style"], 2);
+    assert_eq!(android["androidBadge"], 1);
+    assert_eq!(android["sound"], "114514");
+    assert_eq!(android["warn"], "132");
 }
 
 #[test]
 fn test_ios_badge() {
-    test_pushing(|| TestMsg::default().set_ios(|ios| ios.set_badge(IosBadgeType::Abs(12))));
+    let notify =
+        test_pushing(|| TestMsg::default().set_ios(|ios| ios.set_badge(IosBadgeType::Abs(12))));
+    assert_eq!(notify["iosNotify"], json!({"badge": 12, "badgeType": 1}));
 }
 
 #[test]
 fn test_ios_subtitle() {
-    test_pushing(|| TestMsg::default().set_ios(|ios| ios.set_subtitle("小可试探副标题".into())))
+    let notify = test_pushing(|| {
+        TestMsg::default().set_ios(|ios| ios.set_subtitle("小可试探副标题".into()))
+    });
+    assert_eq!(notify["iosNotify"], json!({"subtitle": "小可试探副标题"}));
 }
 
 #[test]
 fn test_ios_no_sound() {
-    test_pushing(|| TestMsg::default().set_ios(|ios| ios.set_sound(IosPushSound::None)))
+    let notify =
+        test_pushing(|| TestMsg::default().set_ios(|ios| ios.set_sound(IosPushSound::None)));
+    assert_eq!(notify["iosNotify"], json!({"sound": null}));
 }
 
 #[test]
 fn test_ios_rich() {
     let img = "https://i2.hdslb.com/bfs/archive/a995572283104e306e433240b47fba772c4ed3a0.jpg@672w_378h_1c";
-    test_pushing(|| {
+    let notify = test_pushing(|| {
         TestMsg::default().set_ios(|ios| ios.set_rich_text(IosRichTextType::Picture(img.into())))
-    })
+    });
+    assert_eq!(
+        notify["iosNotify"],
+        json!({"attachment": img, "attachmentType": 1, "mutableContent": 1})
+    );
+}
+
+#[test]
+fn test_recording_client() {
+    let client = RecordingClient::new();
+    client
+        .script(MockResponse::Transport("connection reset".into()))
+        .script(MockResponse::new_success("batch-1"));
+
+    let (mut result_rx, errors) = block_on(run_pushing(
+        client.clone(),
+        Manage,
+        RecordingClient::config(),
+        vec![TestMsg::default()],
+        |mob_push| mob_push.subscribe_result(1),
+    ));
+    assert!(errors.is_empty());
+
+    client.assert_push_count(2);
+    client.assert_all_signed(&RecordingClient::config());
+    assert_eq!(
+        client.pushes()[0]["pushTarget"]["rids"][0],
+        "65l05lvwtep0fls"
+    );
+    let result = result_rx.try_recv().unwrap();
+    assert_eq!(result.batch_ids().collect::<Vec<_>>(), ["batch-1"]);
+}
+
+#[test]
+fn test_missing_batch_id() {
+    let client = RecordingClient::new();
+    client.script(MockResponse::Http {
+        status: 200,
+        body: r#"{"status": 200}"#.into(),
+    });
+
+    let (mut result_rx, errors) = block_on(run_pushing(
+        client,
+        Manage,
+        RecordingClient::config(),
+        vec![TestMsg::default()],
+        |mob_push| mob_push.subscribe_result(1),
+    ));
+    assert!(errors.is_empty());
+
+    // 缺少批次 ID 时不以空字符串代替
+    let result = result_rx.try_recv().unwrap();
+    assert_eq!(result.batches.len(), 1);
+    assert_eq!(result.batches[0].batch_id, None);
+    assert_eq!(result.batch_ids().count(), 0);
+}
+
+#[test]
+fn test_http_error_body() {
+    let client = RecordingClient::new();
+    client.script(MockResponse::Http {
+        status: 404,
+        body: format!("<html><body>{}</body></html>", "不存在".repeat(200)),
+    });
+
+    let ((), mut errors) = block_on(run_pushing(
+        client.clone(),
+        Manage,
+        RecordingClient::config(),
+        vec![TestMsg::default()],
+        |_| (),
+    ));
+    client.assert_push_count(1);
+
+    assert_eq!(errors.len(), 1);
+    let err = errors.remove(0);
+    let MobPushError::Batch { error, .. } = err else {
+        panic!("unexpected error {err:?}")
+    };
+    let MobPushError::Http { status, body } = *error else {
+        panic!("unexpected error {error:?}")
+    };
+    assert_eq!(status, 404);
+    assert!(body.starts_with("<html>"));
+    assert_eq!(body.chars().count(), mob_push::api::MAX_ERROR_BODY + 3);
+}
+
+#[test]
+fn test_error_channel() {
+    const MESSAGES: usize = 20;
+
+    let client = RecordingClient::new();
+    for _ in 0..MESSAGES {
+        client.script(MockResponse::Http {
+            status: 400,
+            body: "bad request".into(),
+        });
+    }
+
+    // 异常数量超出 channel 容量时, 推送器等待接收方读取而不丢弃异常
+    let ((), errors) = block_on(run_pushing(
+        client.clone(),
+        Manage,
+        RecordingClient::config(),
+        (0..MESSAGES).map(|_| TestMsg::default()).collect(),
+        |mob_push| {
+            mob_push.set_rate_limit(FAST).unwrap();
+        },
+    ));
+    client.assert_push_count(MESSAGES);
+    assert_eq!(errors.len(), MESSAGES);
+}
+
+/// 记录全部调用的内存持久化队列
+#[derive(Default)]
+struct MemoryOutbox {
+    recovered: std::sync::Mutex<Vec<OutboxEntry<TestMsg>>>,
+    ops: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl MemoryOutbox {
+    fn record(&self, op: String) {
+        self.ops.lock().unwrap().push(op);
+    }
+}
+
+#[async_trait::async_trait]
+impl Outbox<TestMsg> for MemoryOutbox {
+    type Error = Infallible;
+
+    async fn accept(&self, _data: &TestMsg) -> Result<OutboxId, Self::Error> {
+        self.record("accept 8".into());
+        Ok(8)
+    }
+
+    async fn plan(&self, id: OutboxId, batches: &[PlannedBatch]) -> Result<(), Self::Error> {
+        self.record(format!("plan {id} {}", batches.len()));
+        Ok(())
+    }
+
+    async fn planned(&self, id: OutboxId) -> Result<(), Self::Error> {
+        self.record(format!("planned {id}"));
+        Ok(())
+    }
+
+    async fn ack(&self, id: OutboxId, index: usize) -> Result<(), Self::Error> {
+        self.record(format!("ack {id} {index}"));
+        Ok(())
+    }
+
+    async fn complete(&self, id: OutboxId) -> Result<(), Self::Error> {
+        self.record(format!("complete {id}"));
+        Ok(())
+    }
+
+    async fn recover(&self) -> Result<Vec<OutboxEntry<TestMsg>>, Self::Error> {
+        Ok(std::mem::take(&mut *self.recovered.lock().unwrap()))
+    }
+}
+
+#[test]
+fn test_outbox_resume() {
+    let batch = |rid: &str| PlannedBatch {
+        target: PushTarget::new_rids([rid]),
+        platforms: Platforms::default(),
+        passthrough: false,
+        delivery: PlannedDelivery::Normal,
+    };
+    let outbox = MemoryOutbox::default();
+    outbox.recovered.lock().unwrap().push(OutboxEntry {
+        id: 7,
+        data: TestMsg::default(),
+        plan: vec![batch("acked"), batch("unsent")],
+        planned: true,
+        acked: [0].into(),
+    });
+    // 规划中断的消息, 已规划的用户不再推送
+    outbox.recovered.lock().unwrap().push(OutboxEntry {
+        id: 9,
+        data: TestMsg::default(),
+        plan: vec![batch("65l05lvwtep0fls")],
+        planned: false,
+        acked: [0].into(),
+    });
+    let ops = outbox.ops.clone();
+
+    let client = RecordingClient::new();
+    let ((), errors) = block_on(run_pushing(
+        client.clone(),
+        Manage,
+        RecordingClient::config(),
+        vec![TestMsg::default()],
+        |mob_push| {
+            mob_push.set_outbox(outbox);
+        },
+    ));
+    assert!(errors.is_empty());
+
+    // 已被接受的批次不再推送
+    client.assert_push_count(2);
+    assert_eq!(client.pushes()[0]["pushTarget"]["rids"][0], "unsent");
+    assert_eq!(
+        *ops.lock().unwrap(),
+        [
+            "ack 7 1",
+            "complete 7",
+            "planned 9",
+            "complete 9",
+            "accept 8",
+            "plan 8 1",
+            "ack 8 0",
+            "planned 8",
+            "complete 8"
+        ]
+    );
+}
+
+#[test]
+fn test_dedup_workno() {
+    let client = RecordingClient::new();
+    client.script(MockResponse::Transport("timeout".into()));
+
+    let msg = || TestMsg {
+        identity: Some("cookie-1"),
+        ..Default::default()
+    };
+    let ((), errors) = block_on(run_pushing(
+        client.clone(),
+        Manage,
+        RecordingClient::config(),
+        vec![msg(), msg(), TestMsg::default()],
+        |mob_push| {
+            mob_push.set_dedup_window(Duration::from_secs(60));
+        },
+    ));
+    assert!(errors.is_empty());
+
+    // 重试与去重
+    client.assert_push_count(3);
+    let pushes = client.pushes();
+    let workno = format!("{:x}-0", md5::compute("cookie-1"));
+    assert_eq!(pushes[0]["workno"], workno);
+    assert_eq!(pushes[1]["workno"], workno);
+    assert!(pushes[2].get("workno").is_none());
+}
+
+#[test]
+fn test_dedup_after_failure() {
+    let client = RecordingClient::new();
+    client.script(MockResponse::new_mob(400, "bad request"));
+
+    let msg = || TestMsg {
+        identity: Some("cookie-1"),
+        ..Default::default()
+    };
+    let (mut result_rx, errors) = block_on(run_pushing(
+        client.clone(),
+        Manage,
+        RecordingClient::config(),
+        vec![msg(), msg()],
+        |mob_push| {
+            mob_push.set_dedup_window(Duration::from_secs(60));
+            mob_push.subscribe_result(2)
+        },
+    ));
+    assert_eq!(errors.len(), 1);
+
+    // 全部批次推送失败的消息不占用去重窗口
+    client.assert_push_count(2);
+    let result = result_rx.try_recv().unwrap();
+    assert_eq!(result.batch_ids().count(), 1);
+    assert!(result_rx.try_recv().is_err());
+}
+
+/// 屏蔽了全部数据源的用户筛选器
+struct MutedFilter {
+    muted: bool,
+}
+
+impl SubscribeFilter for MutedFilter {
+    type Data = TestMsg;
+
+    type Err = Infallible;
+
+    fn filter(
+        &self,
+        input: impl Iterator<Item = Self::Data>,
+    ) -> Result<Vec<Self::Data>, Self::Err> {
+        Ok(input.filter(|_| !self.muted).collect())
+    }
+
+    fn contains(&self, _target: &<Self::Data as PushEntity>::Resource) -> Result<bool, Self::Err> {
+        Ok(!self.muted)
+    }
+}
+
+/// 分页返回订阅用户, `u1` 屏蔽了全部数据源, `u3` 使用 `quiet_hours` 作为免打扰时段
+#[derive(Default)]
+struct PagedManage {
+    filter_fetched: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    quiet_hours: Option<QuietHours>,
+}
+
+#[async_trait::async_trait]
+impl UserSubscribeManage for PagedManage {
+    type UserIdentify = User;
+
+    type PushData = TestMsg;
+
+    type Filter = MutedFilter;
+
+    type Err = Infallible;
+
+    async fn fetch_subscribe_filter(
+        &self,
+        user_id: &Self::UserIdentify,
+    ) -> Result<Self::Filter, Self::Err> {
+        self.filter_fetched
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(MutedFilter {
+            muted: user_id.mob_id == "u1",
+        })
+    }
+
+    async fn check_subscribed(
+        &self,
+        user_id: &Self::UserIdentify,
+        data_resource: &<Self::PushData as PushEntity>::Resource,
+    ) -> Result<bool, Self::Err> {
+        self.fetch_subscribe_filter(user_id)
+            .await?
+            .contains(data_resource)
+    }
+
+    async fn fetch_all_subscriber(
+        &self,
+        _data_resource: &<Self::PushData as PushEntity>::Resource,
+    ) -> Result<Vec<Self::UserIdentify>, Self::Err> {
+        unreachable!("subscribers are fetched by pages")
+    }
+
+    fn fetch_subscriber_pages<'a>(
+        &'a self,
+        _data_resource: &'a <Self::PushData as PushEntity>::Resource,
+    ) -> BoxStream<'a, Result<Vec<Self::UserIdentify>, Self::Err>> {
+        let page = |ids: &[&str]| {
+            Ok(ids
+                .iter()
+                .map(|id| User {
+                    mob_id: id.to_string(),
+                    quiet_hours: self.quiet_hours.filter(|_| *id == "u3"),
+                })
+                .collect())
+        };
+        stream::iter([page(&["u0"]), page(&["u1", "u2"]), page(&["u3", "u4"])]).boxed()
+    }
+}
+
+#[test]
+fn test_subscriber_pages() {
+    let client = RecordingClient::new();
+    let mut config = RecordingClient::config();
+    config.batch_size = 2;
+
+    let ((), errors) = block_on(run_pushing(
+        client.clone(),
+        PagedManage::default(),
+        config,
+        vec![TestMsg::default()],
+        |mob_push| {
+            mob_push.set_rate_limit(FAST).unwrap();
+        },
+    ));
+    assert!(errors.is_empty());
+
+    let rids = client
+        .pushes()
+        .iter()
+        .map(|push| push["pushTarget"]["rids"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rids,
+        [json!(["u0", "u1"]), json!(["u2", "u3"]), json!(["u4"])]
+    );
+}
+
+#[test]
+fn test_subscribe_check() {
+    let client = RecordingClient::new();
+    let manage = PagedManage::default();
+    let filter_fetched = manage.filter_fetched.clone();
+
+    let ((), errors) = block_on(run_pushing(
+        client.clone(),
+        manage,
+        RecordingClient::config(),
+        vec![TestMsg::default(), TestMsg::default()],
+        |mob_push| {
+            mob_push.set_rate_limit(FAST).unwrap().set_subscribe_check(
+                SubscribeCheck::builder()
+                    .concurrency(2)
+                    .cache_ttl(Duration::from_secs(60))
+                    .build(),
+            );
+        },
+    ));
+    assert!(errors.is_empty());
+
+    // 屏蔽的用户被跳过
+    for push in client.pushes() {
+        assert_eq!(push["pushTarget"]["rids"], json!(["u0", "u2", "u3", "u4"]));
+    }
+    client.assert_push_count(2);
+    // 第二条消息使用缓存的筛选器
+    assert_eq!(filter_fetched.load(std::sync::atomic::Ordering::SeqCst), 5);
+}
+
+#[test]
+fn test_quiet_hours() {
+    // 当前时间前后各一小时为免打扰时段
+    let offset = chrono::FixedOffset::east_opt(8 * 3600).unwrap();
+    let now = chrono::Utc::now().with_timezone(&offset);
+    let quiet = QuietHours::new(
+        offset,
+        (now - chrono::Duration::hours(1)).time(),
+        (now + chrono::Duration::hours(1)).time(),
+    );
+    let end = quiet.end_after(&now).expect("now is quiet");
+
+    for mode in [QuietHoursMode::Defer, QuietHoursMode::Silent] {
+        let client = RecordingClient::new();
+        let manage = PagedManage {
+            quiet_hours: Some(quiet),
+            ..Default::default()
+        };
+        let ((), errors) = block_on(run_pushing(
+            client.clone(),
+            manage,
+            RecordingClient::config(),
+            vec![TestMsg::default()],
+            |mob_push| {
+                mob_push.set_rate_limit(FAST).unwrap().set_quiet_hours(mode);
+            },
+        ));
+        assert!(errors.is_empty());
+
+        // 其余用户照常立即推送
+        let pushes = client.pushes();
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(
+            pushes[0]["pushTarget"]["rids"],
+            json!(["u0", "u1", "u2", "u4"])
+        );
+        assert!(pushes[0]["pushNotify"].get("taskTime").is_none());
+        assert!(pushes[0]["pushNotify"].get("androidNotify").is_none());
+
+        let quiet_push = &pushes[1];
+        assert_eq!(quiet_push["pushTarget"]["rids"], json!(["u3"]));
+        let notify = &quiet_push["pushNotify"];
+        match mode {
+            QuietHoursMode::Defer => {
+                assert_eq!(notify["taskCron"], 1);
+                assert_eq!(notify["taskTime"], end.timestamp_millis());
+            }
+            QuietHoursMode::Silent => {
+                assert!(notify.get("taskTime").is_none());
+                assert_eq!(notify["androidNotify"]["warn"], "3");
+                assert!(notify["androidNotify"].get("sound").is_none());
+                assert_eq!(
+                    notify["iosNotify"].get("sound"),
+                    Some(&serde_json::Value::Null)
+                );
+            }
+        }
+    }
 }